@@ -0,0 +1,434 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{check, load_pathway, load_solution, Args};
+
+/// A (model, solution) pair of the batch
+#[derive(Debug)]
+struct Instance {
+    name: String,
+    model: PathBuf,
+    solution: PathBuf,
+    var_map: Option<PathBuf>,
+}
+
+/// Row of the summary table
+#[derive(Debug, Serialize)]
+struct SummaryRow {
+    instance: String,
+    seeds: Option<usize>,
+    iterations: Option<u32>,
+    reachable: Option<bool>,
+    /// Only with --minimality
+    minimal: Option<bool>,
+    /// Only with --time-check
+    time_consistent: Option<bool>,
+    /// All the requested checks passed
+    passed: bool,
+    error: Option<String>,
+}
+
+/// Checks all the instances and writes the summary, returns true if every
+/// check passed
+pub fn run(path: &Path, args: &Args) -> bool {
+    let var_map = args.var_map.as_deref();
+    let instances = if path.is_dir() {
+        find_instances(path, var_map)
+    } else {
+        read_manifest(path, var_map)
+    };
+    info!("Checking {} instances", instances.len());
+
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(jobs) = args.jobs {
+        pool = pool.num_threads(jobs);
+    }
+    let pool = pool.build().expect("Can't start the thread pool");
+
+    let rows: Vec<SummaryRow> = pool.install(|| {
+        instances
+            .par_iter()
+            .map(|instance| check_instance(instance, args))
+            .collect()
+    });
+
+    let passed = rows.iter().all(|r| r.passed);
+
+    write_summary(&rows, args.summary.as_deref());
+    passed
+}
+
+fn check_instance(instance: &Instance, args: &Args) -> SummaryRow {
+    info!("Checking {}", instance.name);
+
+    // the single instance checks panic on malformed input
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let pw = load_pathway(&instance.model);
+        let solution = load_solution(&instance.solution, args);
+        check(&pw, &solution, instance.var_map.as_deref(), args)
+    }));
+
+    match result {
+        Ok((report, _)) => SummaryRow {
+            instance: instance.name.clone(),
+            seeds: Some(report.seed_count),
+            iterations: Some(report.iterations),
+            reachable: Some(report.reachable),
+            minimal: report.minimality.as_ref().map(|m| m.minimal),
+            time_consistent: report.time_violations.as_ref().map(|v| v.is_empty()),
+            passed: report.passed(),
+            error: None,
+        },
+        Err(payload) => {
+            let error = payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "unknown error".to_string());
+            warn!("Instance {} failed: {}", instance.name, error);
+            SummaryRow {
+                instance: instance.name.clone(),
+                seeds: None,
+                iterations: None,
+                reachable: None,
+                minimal: None,
+                time_consistent: None,
+                passed: false,
+                error: Some(error),
+            }
+        }
+    }
+}
+
+/// Pathway written by the generator batch in every instance directory
+const GENERATED_MODEL: &str = "post.json";
+
+/// Variable map written by the generator batch with --var-map
+const GENERATED_VAR_MAP: &str = "var.map.json";
+
+/// Extensions of the model.* files written by the generator batch
+const GENERATED_EXTENSIONS: [&str; 3] = ["lp", "mps", "wcnf"];
+
+/// Walks the batch directory and its subdirectories. A directory written by
+/// the generator batch (<name>/post.json) is paired with its model.* files,
+/// except the generated model.lp, model.mps and model.wcnf, and with its
+/// var.map.json. Elsewhere every <name>.json file is a model, unless another
+/// model name is a prefix of it, and every other <name>.* file is one of its
+/// solutions, the variable map <name>.map.json excluded. Without a variable
+/// map next to the model --var-map is used.
+fn find_instances(root: &Path, var_map: Option<&Path>) -> Vec<Instance> {
+    let mut instances = vec![];
+    visit_directory(root, root, var_map, &mut instances);
+    instances
+}
+
+fn visit_directory(root: &Path, dir: &Path, var_map: Option<&Path>, instances: &mut Vec<Instance>) {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("Can't read the batch directory")
+        .map(|e| e.expect("Can't read the batch directory").path())
+        .filter(|p| !file_name(p).starts_with('.'))
+        .collect();
+    paths.sort();
+
+    let (dirs, files): (Vec<PathBuf>, Vec<PathBuf>) = paths.into_iter().partition(|p| p.is_dir());
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    let instance_name = |name: &str| relative.join(name).to_string_lossy().into_owned();
+    let local_var_map = |path: PathBuf| {
+        if path.is_file() {
+            Some(path)
+        } else {
+            var_map.map(Path::to_path_buf)
+        }
+    };
+
+    if files.iter().any(|f| file_name(f) == GENERATED_MODEL) {
+        let solutions: Vec<&PathBuf> = files
+            .iter()
+            .filter(|f| {
+                let name = file_name(f);
+                name.strip_prefix("model.")
+                    .is_some_and(|e| !GENERATED_EXTENSIONS.contains(&e))
+            })
+            .collect();
+
+        // the directory name is enough unless it has more than one solution
+        for solution in &solutions {
+            instances.push(Instance {
+                name: if solutions.len() == 1 {
+                    relative.to_string_lossy().into_owned()
+                } else {
+                    instance_name(&file_name(solution))
+                },
+                model: dir.join(GENERATED_MODEL),
+                solution: solution.to_path_buf(),
+                var_map: local_var_map(dir.join(GENERATED_VAR_MAP)),
+            });
+        }
+    } else {
+        let stems: Vec<String> = files
+            .iter()
+            .filter_map(|f| file_name(f).strip_suffix(".json").map(str::to_string))
+            .filter(|s| !s.ends_with(".map"))
+            .collect();
+        let is_solution_of = |name: &str, stem: &str| {
+            name.strip_prefix(stem)
+                .is_some_and(|rest| rest.starts_with('.'))
+        };
+        // a.json is the model of a.clingo.json
+        let models: Vec<&String> = stems
+            .iter()
+            .filter(|s| !stems.iter().any(|m| m != *s && is_solution_of(s, m)))
+            .collect();
+
+        for stem in models {
+            let model_name = format!("{}.json", stem);
+            let solutions: Vec<&PathBuf> = files
+                .iter()
+                .filter(|f| {
+                    let name = file_name(f);
+                    is_solution_of(&name, stem)
+                        && name != model_name
+                        && !name.ends_with(".map.json")
+                })
+                .collect();
+
+            for solution in &solutions {
+                instances.push(Instance {
+                    name: if solutions.len() == 1 {
+                        instance_name(stem)
+                    } else {
+                        instance_name(&file_name(solution))
+                    },
+                    model: dir.join(&model_name),
+                    solution: solution.to_path_buf(),
+                    var_map: local_var_map(dir.join(format!("{}.map.json", stem))),
+                });
+            }
+        }
+    }
+
+    for subdir in &dirs {
+        visit_directory(root, subdir, var_map, instances);
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+/// One "model solution [variable map]" line per instance (fields separated
+/// by whitespace or a comma), relative paths start from the manifest
+/// directory. The instance is named after the solution path without the
+/// extension. For a generator batch:
+///
+/// ```text
+/// # model, solution, variable map
+/// out/ecoli/post.json out/ecoli/model.sol out/ecoli/var.map.json
+/// out/yeast/post.json out/yeast/model.json
+/// ```
+fn read_manifest(path: &Path, var_map: Option<&Path>) -> Vec<Instance> {
+    let text = fs::read_to_string(path).expect("Can't read the manifest");
+    let base = path.parent().unwrap_or(Path::new("."));
+
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|f| !f.is_empty())
+                .collect();
+            let (model, solution, map) = match fields[..] {
+                [model, solution] => (model, solution, None),
+                [model, solution, map] => (model, solution, Some(base.join(map))),
+                _ => panic!("Incorrect manifest line: {}", line),
+            };
+            Instance {
+                name: Path::new(solution)
+                    .with_extension("")
+                    .to_string_lossy()
+                    .into_owned(),
+                model: base.join(model),
+                solution: base.join(solution),
+                var_map: map.or_else(|| var_map.map(Path::to_path_buf)),
+            }
+        })
+        .collect()
+}
+
+fn write_summary(rows: &[SummaryRow], path: Option<&Path>) {
+    let json = path.is_some_and(|p| p.extension().is_some_and(|e| e == "json"));
+
+    if json {
+        let file = File::create(path.unwrap()).expect("Can't create summary");
+        serde_json::to_writer_pretty(file, rows).expect("Can't write summary");
+        return;
+    }
+
+    let output: Box<dyn Write> = match path {
+        Some(path) => Box::new(File::create(path).expect("Can't create summary")),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(output);
+    for row in rows {
+        writer.serialize(row).expect("Can't write summary");
+    }
+    writer.flush().expect("Can't write summary");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn batch_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("checker-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for file in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    fn summary(
+        instances: &[Instance],
+        dir: &Path,
+    ) -> Vec<(String, String, String, Option<String>)> {
+        let relative = |p: &Path| p.strip_prefix(dir).unwrap().to_string_lossy().into_owned();
+        instances
+            .iter()
+            .map(|i| {
+                (
+                    i.name.clone(),
+                    relative(&i.model),
+                    relative(&i.solution),
+                    i.var_map.as_deref().map(relative),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_instances_flat() {
+        let dir = batch_dir(
+            "flat",
+            &[
+                "a.json",
+                "a.map.json",
+                "a.clingo.json",
+                "b.json",
+                "b.sol",
+                "b.mst",
+            ],
+        );
+        let instances = find_instances(&dir, None);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected = vec![
+            (
+                "a".to_string(),
+                "a.json".to_string(),
+                "a.clingo.json".to_string(),
+                Some("a.map.json".to_string()),
+            ),
+            (
+                "b.mst".to_string(),
+                "b.json".to_string(),
+                "b.mst".to_string(),
+                None,
+            ),
+            (
+                "b.sol".to_string(),
+                "b.json".to_string(),
+                "b.sol".to_string(),
+                None,
+            ),
+        ];
+        assert_eq!(summary(&instances, &dir), expected);
+    }
+
+    #[test]
+    fn test_find_instances_generator_batch() {
+        let dir = batch_dir(
+            "generated",
+            &[
+                "manifest.csv",
+                "ecoli/pre.json",
+                "ecoli/post.json",
+                "ecoli/var.map.json",
+                "ecoli/model.lp",
+                "ecoli/model.sol",
+                "plants/yeast/post.json",
+                "plants/yeast/model.lp",
+                "plants/yeast/model.json",
+                "plants/yeast/model.mst",
+            ],
+        );
+        let global_map = dir.join("global.map.json");
+        let instances = find_instances(&dir, Some(&global_map));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected = vec![
+            (
+                "ecoli".to_string(),
+                "ecoli/post.json".to_string(),
+                "ecoli/model.sol".to_string(),
+                Some("ecoli/var.map.json".to_string()),
+            ),
+            (
+                "plants/yeast/model.json".to_string(),
+                "plants/yeast/post.json".to_string(),
+                "plants/yeast/model.json".to_string(),
+                Some("global.map.json".to_string()),
+            ),
+            (
+                "plants/yeast/model.mst".to_string(),
+                "plants/yeast/post.json".to_string(),
+                "plants/yeast/model.mst".to_string(),
+                Some("global.map.json".to_string()),
+            ),
+        ];
+        assert_eq!(summary(&instances, &dir), expected);
+    }
+
+    #[test]
+    fn test_read_manifest() {
+        let dir = batch_dir("manifest", &[]);
+        fs::create_dir_all(&dir).unwrap();
+        let manifest = dir.join("manifest.txt");
+        fs::write(
+            &manifest,
+            "# model, solution, variable map\n\
+             ecoli/post.json ecoli/model.sol ecoli/var.map.json\n\
+             yeast/post.json,yeast/model.json\n",
+        )
+        .unwrap();
+        let instances = read_manifest(&manifest, None);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected = vec![
+            (
+                "ecoli/model".to_string(),
+                "ecoli/post.json".to_string(),
+                "ecoli/model.sol".to_string(),
+                Some("ecoli/var.map.json".to_string()),
+            ),
+            (
+                "yeast/model".to_string(),
+                "yeast/post.json".to_string(),
+                "yeast/model.json".to_string(),
+                None,
+            ),
+        ];
+        assert_eq!(summary(&instances, &dir), expected);
+    }
+}
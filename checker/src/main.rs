@@ -1,165 +1,391 @@
+mod batch;
+mod report;
+mod timeline;
+
 use clap::Parser;
-use log::info;
-use msstools::pw::Pathway;
-use serde::{Deserialize, Serialize};
-use serde_xml_rs::from_reader;
+use clap::ValueEnum;
+use log::{info, warn};
+use msstools::encoders::asp::parse_clingo_json;
+use msstools::encoders::wcnf::{parse_maxsat_output, read_mapping};
+use msstools::models::bigmmodel::BigMModel;
+use msstools::models::mssmodel::{time_violations, MssModel};
+use msstools::models::newmodel::NewModel;
+use msstools::models::timesetmodel::TimeSetModel;
+use msstools::pw::{check_minimality, explain_unreached, Pathway};
+use msstools::solutions::{
+    self, fractional_values, is_one, read_solution, read_variable_map, seed_names, Solution,
+    SolutionContent, DEFAULT_TOLERANCE,
+};
+use report::{FractionalValue, Report};
+use std::cmp::min;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs::File, io::BufReader};
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename = "CPLEXSolution")]
-struct Solution {
-    #[serde(rename = "header")]
-    header: Header,
-    #[serde(rename = "linearConstraints")]
-    constraints: LinearConstraints,
-    #[serde(rename = "variables")]
-    variables: Variables,
-    #[serde(rename = "objectiveValues")]
-    objective: Objective,
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum SolutionFormat {
+    /// Detect the format from the file content
+    Auto,
+    Cplex,
+    Gurobi,
+    Cbc,
+    Glpk,
+    Highs,
+    Scip,
+    /// Compound names, one per line
+    Seeds,
+    Maxsat,
+    Clingo,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Header {
-    #[serde(rename = "problemName")]
-    problem_name: String,
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum ModelType {
+    Timeset,
+    Bigm,
+    New,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct LinearConstraints {
-    #[serde(rename = "constraint")]
-    constraints: Vec<Constraint>,
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum ReportFormat {
+    /// Iteration count on stdout, result in the exit code
+    Plain,
+    /// JSON report on stdout
+    Json,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Constraint {
-    #[serde(rename = "name")]
-    name: String,
-    #[serde(rename = "index")]
-    index: usize,
-    #[serde(rename = "slack")]
-    slack: f32,
-}
+#[derive(Parser)]
+struct Args {
+    /// Json model file
+    #[arg(required_unless_present = "batch")]
+    model: Option<PathBuf>,
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Variables {
-    #[serde(rename = "variable")]
-    variables: Vec<Variable>,
-}
+    /// Solution file
+    #[arg(required_unless_present = "batch")]
+    solution: Option<PathBuf>,
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Variable {
-    #[serde(rename = "name")]
-    name: String,
-    #[serde(rename = "index")]
-    index: usize,
-    #[serde(rename = "value")]
-    value: f32,
-}
+    /// Check many instances in parallel: either a directory, searched
+    /// recursively, where every <name>.json model is paired with the <name>.*
+    /// solutions next to it and every <name>/post.json written by the
+    /// generator batch with the <name>/model.* solutions, or a manifest with
+    /// a "model solution [variable map]" line for each instance
+    #[arg(long, conflicts_with_all = ["model", "solution"])]
+    batch: Option<PathBuf>,
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Objective {
-    #[serde(rename = "objective")]
-    objective: Vec<ObjectiveValue>,
-}
+    /// Summary table of the batch mode (.csv or .json, default: CSV on stdout)
+    #[arg(long, requires = "batch")]
+    summary: Option<PathBuf>,
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ObjectiveValue {
-    #[serde(rename = "name")]
-    name: String,
-    #[serde(rename = "index")]
-    index: usize,
-    #[serde(rename = "value")]
-    value: f32,
-}
+    /// Number of instances checked in parallel (default: one per core)
+    #[arg(long, requires = "batch")]
+    jobs: Option<usize>,
 
-#[derive(Parser)]
-struct Args {
-    /// Json model file
-    model: PathBuf,
+    /// Solution file type (auto reads .json files as clingo output)
+    #[arg(long, short, default_value = "auto")]
+    format: SolutionFormat,
 
-    /// CPLEX solution file
-    solution: PathBuf,
-}
+    /// Seed variable to compound name map written by the generator;
+    /// when given the seeds are matched by compound name. In batch mode
+    /// the <name>.map.json or var.map.json next to the model comes first
+    #[arg(long)]
+    var_map: Option<PathBuf>,
 
-fn main() {
-    env_logger::init();
-    let args = Args::parse();
+    /// Also check that no seed is redundant and that no one-in/one-out swap
+    /// leads to a smaller seed set (exit code 1 if the set is not minimal).
+    /// The swap search computes a closure only for the pairs of seeds with
+    /// common ancestors, once per candidate compound
+    #[arg(long)]
+    minimality: bool,
 
-    let model_name = args.model;
-    let solution_name = args.solution;
+    /// Check the time variables of the given model type against the
+    /// activation steps of the seed set (exit code 1 if a compound is
+    /// claimed earlier than it can be reached)
+    #[arg(long)]
+    time_check: Option<ModelType>,
 
-    let model_file = File::open(model_name).expect("Can't open model file");
-    let model_reader = BufReader::new(model_file);
+    /// T/M the model was generated with
+    #[arg(long, short, default_value_t = 10)]
+    time: i32,
+
+    /// Explain why the unreached compounds are not produced (printed on
+    /// stderr with the plain output)
+    #[arg(long)]
+    explain: bool,
+
+    /// Integrality tolerance: values within it from 1 are seeds, values
+    /// farther than it from an integer are reported as fractional
+    #[arg(long, default_value_t = DEFAULT_TOLERANCE)]
+    tolerance: f64,
+
+    /// Output format
+    #[arg(long, default_value = "plain")]
+    report: ReportFormat,
+
+    /// Variable mapping written by the generator (required for maxsat)
+    #[arg(long)]
+    mapping: Option<PathBuf>,
 
-    let solution_file = File::open(solution_name).expect("Can't open solution file");
-    let read_sol = BufReader::new(solution_file);
-    let sol: Solution = from_reader(read_sol).expect("Can't read xml");
+    /// Write the step and the producing reaction of every reached compound
+    /// (.json or .csv)
+    #[arg(long, conflicts_with = "batch")]
+    timeline: Option<PathBuf>,
 
-    let xvals = sol
-        .variables
-        .variables
+    /// Directory where a DOT frame of the network is written for every
+    /// closure step
+    #[arg(long, conflicts_with = "batch")]
+    frames: Option<PathBuf>,
+
+    /// Also render the frames to SVG (requires Graphviz)
+    #[arg(long, requires = "frames")]
+    svg: bool,
+}
+
+fn seed_ids(pw: &Pathway, names: &[String]) -> HashSet<u32> {
+    names
         .iter()
-        .filter(|x| x.name.starts_with("x") && x.value == 1.0);
+        .map(|name| {
+            pw.get_compound_option(name)
+                .unwrap_or_else(|| panic!("Unknown compound {}", name))
+        })
+        .collect()
+}
 
-    // Add elements in a set and do "apply the reactions until
-    // they have no effect anymore or the set is complete".
+fn load_pathway(path: &Path) -> Pathway {
+    let model_file = File::open(path).expect("Can't open model file");
+    let model_reader = BufReader::new(model_file);
 
     let pw: Pathway = serde_json::from_reader(model_reader).expect("Error parsing json");
     info!("Pathway contains {} reactions", pw.get_reactions_count());
     info!("Pathway contains {} compounds", pw.get_compounds_count());
+    pw
+}
 
-    let mut in_set = HashSet::<u32>::new();
-    for xval in xvals {
-        in_set.insert(
-            xval.name
-                .strip_prefix("x")
-                .expect("Invalid index")
-                .parse()
-                .expect("Invalid index number"),
-        );
-    }
+fn load_solution(path: &Path, args: &Args) -> Solution {
+    let solution_format = match args.format {
+        SolutionFormat::Auto => None,
+        SolutionFormat::Cplex => Some(solutions::SolutionFormat::Cplex),
+        SolutionFormat::Gurobi => Some(solutions::SolutionFormat::Gurobi),
+        SolutionFormat::Cbc => Some(solutions::SolutionFormat::Cbc),
+        SolutionFormat::Glpk => Some(solutions::SolutionFormat::Glpk),
+        SolutionFormat::Highs => Some(solutions::SolutionFormat::Highs),
+        SolutionFormat::Scip => Some(solutions::SolutionFormat::Scip),
+        SolutionFormat::Seeds => Some(solutions::SolutionFormat::SeedList),
+        SolutionFormat::Maxsat | SolutionFormat::Clingo => None,
+    };
 
-    info!("MSS contains {} compounds", in_set.len());
+    // the only JSON solutions are the clingo ones
+    let format = match args.format {
+        SolutionFormat::Auto if path.extension().is_some_and(|e| e == "json") => {
+            SolutionFormat::Clingo
+        }
+        format => format,
+    };
 
-    let mut iteration = 0u32;
-    let mut to_add = Vec::<u32>::new();
-    loop {
-        for reac in pw.get_reactions() {
-            let mut all_substrate = true;
-            for s in reac.get_substrate() {
-                if !in_set.contains(s) {
-                    all_substrate = false;
-                    break;
-                }
+    match format {
+        SolutionFormat::Maxsat => {
+            let mapping_name = args
+                .mapping
+                .as_ref()
+                .expect("The maxsat format requires --mapping");
+            let mapping = read_mapping(mapping_name);
+            let solution_file = File::open(path).expect("Can't open solution file");
+            let values = parse_maxsat_output(BufReader::new(solution_file), &mapping);
+            Solution {
+                content: SolutionContent::Values(values),
+                objective: None,
+                slack: None,
             }
-            if all_substrate {
-                for p in reac.get_product() {
-                    if !in_set.contains(p) {
-                        to_add.push(p.clone());
-                    }
-                }
+        }
+        SolutionFormat::Clingo => {
+            let solution_file = File::open(path).expect("Can't open solution file");
+            Solution {
+                content: SolutionContent::Seeds(parse_clingo_json(BufReader::new(solution_file))),
+                objective: None,
+                slack: None,
             }
         }
+        _ => read_solution(path, solution_format).expect("Can't read solution"),
+    }
+}
 
-        iteration += 1;
+/// Checks the solution and returns the report together with the seed set
+fn check(
+    pw: &Pathway,
+    solution: &Solution,
+    var_map: Option<&Path>,
+    args: &Args,
+) -> (Report, HashSet<u32>) {
+    let content = &solution.content;
+    let tolerance = args.tolerance;
 
-        if to_add.len() == 0 || in_set.len() == pw.get_compounds_count() {
-            break;
-        }
-
-        in_set.extend(to_add.iter());
-        to_add.clear();
+    let fractional = match content {
+        SolutionContent::Values(values) => fractional_values(values, tolerance),
+        SolutionContent::Seeds(_) => vec![],
+    };
+    for (name, value) in &fractional {
+        warn!("Variable {} has fractional value {}", name, value);
+    }
+    if !fractional.is_empty() {
+        warn!(
+            "{} variables are fractional with tolerance {}",
+            fractional.len(),
+            tolerance
+        );
     }
-    info!("Completed {} iterations, ", iteration);
-    print!("{}", iteration);
 
-    if in_set.len() == pw.get_compounds_count() {
+    let in_set: HashSet<u32> = match content {
+        SolutionContent::Values(values) => match var_map {
+            Some(map_path) => {
+                let map = read_variable_map(map_path);
+                seed_ids(pw, &seed_names(values, &map, tolerance))
+            }
+            None => values
+                .iter()
+                .filter(|(name, value)| name.starts_with('x') && is_one(**value, tolerance))
+                .map(|(name, _)| {
+                    name.strip_prefix('x')
+                        .expect("Invalid index")
+                        .parse()
+                        .expect("Invalid index number")
+                })
+                .collect(),
+        },
+        SolutionContent::Seeds(names) => seed_ids(pw, names),
+    };
+
+    info!("MSS contains {} compounds", in_set.len());
+
+    // Apply the reactions until they have no effect anymore; the last
+    // iteration is the one that adds nothing.
+    let closure = pw.closure(&in_set);
+
+    let mut report = Report::new(pw, &in_set, &closure);
+    report.objective = solution.objective;
+    report.slack = solution.slack.clone();
+    report.fractional = fractional
+        .into_iter()
+        .map(|(variable, value)| FractionalValue { variable, value })
+        .collect();
+
+    info!("Completed {} iterations, ", report.iterations);
+
+    if closure.is_complete() {
         info!("set is reachable.");
-        std::process::exit(0);
     } else {
         info!("set is unreachable, completed full iteration with no effect.");
+
+        if args.explain {
+            report.set_explanation(pw, &explain_unreached(pw, &closure));
+        }
+    }
+
+    if args.minimality && closure.is_complete() {
+        let minimality = check_minimality(pw, &in_set);
+        let names = pw.get_compounds();
+
+        for seed in &minimality.redundant {
+            warn!("Seed {} is redundant", names[*seed as usize].name);
+        }
+        for swap in &minimality.swaps {
+            warn!(
+                "Replacing seed {} with {} makes seed {} redundant",
+                names[swap.removed as usize].name,
+                names[swap.added as usize].name,
+                names[swap.redundant as usize].name
+            );
+        }
+
+        if minimality.is_minimal() {
+            info!("set is minimal.");
+        } else {
+            info!("set is not minimal.");
+        }
+        report.set_minimality(pw, &minimality);
+    }
+
+    if let Some(model_type) = args.time_check {
+        let SolutionContent::Values(values) = content else {
+            panic!("The time check requires the variable values");
+        };
+
+        let mut time_m = args.time;
+        if time_m == -1 {
+            time_m = min(pw.get_reactions_count(), pw.get_compounds_count()) as i32;
+        }
+
+        let model: Box<dyn MssModel> = match model_type {
+            ModelType::Timeset => Box::new(TimeSetModel::new(pw, time_m as usize + 2)),
+            ModelType::Bigm => Box::new(BigMModel::new(pw, time_m)),
+            ModelType::New => Box::new(NewModel::new(pw, time_m)),
+        };
+
+        let decoded = model.decode(values, tolerance);
+        let violations = time_violations(model.as_ref(), pw, &decoded);
+        let names = pw.get_compounds();
+
+        for violation in &violations {
+            let name = &names[violation.compound as usize].name;
+            match violation.earliest {
+                Some(earliest) => warn!(
+                    "Compound {} is claimed at time {} but is reached at step {}",
+                    name, violation.claimed, earliest
+                ),
+                None => warn!(
+                    "Compound {} is claimed at time {} but is never reached",
+                    name, violation.claimed
+                ),
+            }
+        }
+
+        if violations.is_empty() {
+            info!("times are consistent with the {} model.", model.name());
+        } else {
+            info!("{} compounds have inconsistent times.", violations.len());
+        }
+        report.set_time_violations(pw, &violations);
+    }
+
+    (report, in_set)
+}
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    if let Some(batch_path) = &args.batch {
+        let passed = batch::run(batch_path, &args);
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    let pw = load_pathway(args.model.as_ref().unwrap());
+    let solution = load_solution(args.solution.as_ref().unwrap(), &args);
+    let (report, seeds) = check(&pw, &solution, args.var_map.as_deref(), &args);
+
+    if let Some(timeline_path) = &args.timeline {
+        timeline::write_timeline(&report.activation, timeline_path);
+    }
+    if let Some(frames_dir) = &args.frames {
+        timeline::write_frames(&pw, &seeds, frames_dir, args.svg);
+    }
+
+    match args.report {
+        ReportFormat::Plain => {
+            if let Some(explanation) = &report.explanation {
+                explanation
+                    .write(&mut std::io::stderr())
+                    .expect("Can't write explanation");
+            }
+            print!("{}", report.iterations)
+        }
+        ReportFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("Can't write report")
+            )
+        }
+    }
+
+    if !report.reachable {
         std::process::exit(-1);
     }
+    std::process::exit(if report.passed() { 0 } else { 1 });
 }
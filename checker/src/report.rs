@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use msstools::models::mssmodel::TimeViolation;
+use msstools::pw::{BlockedCompound, Closure, Explanation, MinimalityReport, Pathway};
+use msstools::solutions::SlackSummary;
+use serde::Serialize;
+
+/// Result of the check, printed with `--report json`
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub seeds: Vec<String>,
+    pub seed_count: usize,
+
+    /// Objective value reported by the solver
+    pub objective: Option<f64>,
+
+    /// Iterations of the closure, as printed in the plain output
+    pub iterations: u32,
+    pub reachable: bool,
+
+    pub activation: Vec<CompoundActivation>,
+    pub unreached: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack: Option<SlackSummary>,
+
+    /// Variables farther than the tolerance from an integer
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fractional: Vec<FractionalValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimality: Option<MinimalityResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_violations: Option<Vec<TimeViolationResult>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ExplanationResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FractionalValue {
+    pub variable: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompoundActivation {
+    pub compound: String,
+
+    /// Closure step in which the compound is reached (0 for the seeds)
+    pub step: Option<u32>,
+
+    /// Reaction that produced the compound
+    pub reaction: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MinimalityResult {
+    pub minimal: bool,
+    pub redundant: Vec<String>,
+    pub swaps: Vec<SwapResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapResult {
+    pub removed: String,
+    pub added: String,
+    pub redundant: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeViolationResult {
+    pub compound: String,
+    pub claimed: u32,
+    pub earliest: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplanationResult {
+    /// Unreached compounds that no reaction produces
+    pub frontier: Vec<String>,
+    pub trees: Vec<BlockedCompoundResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockedCompoundResult {
+    pub compound: String,
+
+    /// Already expanded somewhere else in the trees
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub repeated: bool,
+
+    pub producers: Vec<BlockedReactionResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockedReactionResult {
+    pub reaction: String,
+    pub missing: Vec<BlockedCompoundResult>,
+}
+
+impl BlockedCompoundResult {
+    fn new(pw: &Pathway, blocked: &BlockedCompound) -> Self {
+        BlockedCompoundResult {
+            compound: pw.get_compounds()[blocked.compound as usize].name.clone(),
+            repeated: blocked.repeated,
+            producers: blocked
+                .producers
+                .iter()
+                .map(|r| BlockedReactionResult {
+                    reaction: pw.get_reactions()[r.reaction as usize].get_name().clone(),
+                    missing: r
+                        .missing
+                        .iter()
+                        .map(|c| BlockedCompoundResult::new(pw, c))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, depth: usize) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        if self.repeated {
+            return writeln!(writer, "{}{} (see above)", indent, self.compound);
+        }
+        if self.producers.is_empty() {
+            return writeln!(writer, "{}{} (no producer)", indent, self.compound);
+        }
+
+        writeln!(writer, "{}{}", indent, self.compound)?;
+        for reaction in &self.producers {
+            let missing: Vec<&str> = reaction
+                .missing
+                .iter()
+                .map(|c| c.compound.as_str())
+                .collect();
+            writeln!(
+                writer,
+                "{}  {} missing: {}",
+                indent,
+                reaction.reaction,
+                missing.join(", ")
+            )?;
+            for compound in &reaction.missing {
+                compound.write(writer, depth + 2)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ExplanationResult {
+    /// Writes the trees in a human readable form
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for tree in &self.trees {
+            tree.write(writer, 0)?;
+        }
+        writeln!(writer, "Blocking frontier: {}", self.frontier.join(", "))
+    }
+}
+
+impl Report {
+    pub fn new(pw: &Pathway, seeds: &HashSet<u32>, closure: &Closure) -> Self {
+        let compounds = pw.get_compounds();
+        let reactions = pw.get_reactions();
+
+        let mut seed_ids: Vec<u32> = seeds.iter().copied().collect();
+        seed_ids.sort();
+
+        let activation = compounds
+            .iter()
+            .map(|c| CompoundActivation {
+                compound: c.name.clone(),
+                step: closure.get_activation(c.id),
+                reaction: closure
+                    .get_producer(c.id)
+                    .map(|r| reactions[r as usize].get_name().clone()),
+            })
+            .collect();
+
+        Report {
+            seeds: names(pw, &seed_ids),
+            seed_count: seed_ids.len(),
+            objective: None,
+            iterations: closure.get_steps() + 1,
+            reachable: closure.is_complete(),
+            activation,
+            unreached: names(pw, &closure.get_unreached()),
+            slack: None,
+            fractional: vec![],
+            minimality: None,
+            time_violations: None,
+            explanation: None,
+        }
+    }
+
+    pub fn set_explanation(&mut self, pw: &Pathway, explanation: &Explanation) {
+        self.explanation = Some(ExplanationResult {
+            frontier: names(pw, &explanation.frontier),
+            trees: explanation
+                .trees
+                .iter()
+                .map(|t| BlockedCompoundResult::new(pw, t))
+                .collect(),
+        });
+    }
+
+    pub fn set_minimality(&mut self, pw: &Pathway, report: &MinimalityReport) {
+        let compounds = pw.get_compounds();
+        self.minimality = Some(MinimalityResult {
+            minimal: report.is_minimal(),
+            redundant: names(pw, &report.redundant),
+            swaps: report
+                .swaps
+                .iter()
+                .map(|s| SwapResult {
+                    removed: compounds[s.removed as usize].name.clone(),
+                    added: compounds[s.added as usize].name.clone(),
+                    redundant: compounds[s.redundant as usize].name.clone(),
+                })
+                .collect(),
+        });
+    }
+
+    pub fn set_time_violations(&mut self, pw: &Pathway, violations: &[TimeViolation]) {
+        let compounds = pw.get_compounds();
+        self.time_violations = Some(
+            violations
+                .iter()
+                .map(|v| TimeViolationResult {
+                    compound: compounds[v.compound as usize].name.clone(),
+                    claimed: v.claimed,
+                    earliest: v.earliest,
+                })
+                .collect(),
+        );
+    }
+
+    /// All the requested checks passed
+    pub fn passed(&self) -> bool {
+        self.reachable
+            && self.minimality.as_ref().is_none_or(|m| m.minimal)
+            && self.time_violations.as_ref().is_none_or(|v| v.is_empty())
+    }
+}
+
+fn names(pw: &Pathway, ids: &[u32]) -> Vec<String> {
+    let compounds = pw.get_compounds();
+    ids.iter()
+        .map(|i| compounds[*i as usize].name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use msstools::pw::{Compound, Reaction, SeedSwap};
+
+    use super::*;
+
+    /// A -> B -> C
+    pub(crate) fn chain_pathway() -> Pathway {
+        let mut pathway = Pathway::new();
+        for (id, name) in ["A", "B", "C"].iter().enumerate() {
+            pathway.add_compound(Compound::new(id as u32, name.to_string()));
+        }
+        for id in 0..2 {
+            let mut reaction = Reaction::new(id, format!("R{}", id));
+            reaction.add_substrate(id);
+            reaction.add_product(id + 1);
+            pathway.add_reaction(reaction);
+        }
+        pathway
+    }
+
+    #[test]
+    fn test_report_new() {
+        let pathway = chain_pathway();
+        let seeds = HashSet::from([0]);
+        let report = Report::new(&pathway, &seeds, &pathway.closure(&seeds));
+
+        assert_eq!(report.seeds, vec!["A"]);
+        assert_eq!(report.seed_count, 1);
+        // the plain output counts the step that finds nothing new
+        assert_eq!(report.iterations, 3);
+        assert!(report.reachable);
+        assert!(report.unreached.is_empty());
+
+        let c = &report.activation[2];
+        assert_eq!(c.compound, "C");
+        assert_eq!(c.step, Some(2));
+        assert_eq!(c.reaction.as_deref(), Some("R1"));
+        assert_eq!(report.activation[0].step, Some(0));
+        assert_eq!(report.activation[0].reaction, None);
+        assert!(report.passed());
+
+        let seeds = HashSet::from([1]);
+        let report = Report::new(&pathway, &seeds, &pathway.closure(&seeds));
+        assert!(!report.reachable);
+        assert_eq!(report.unreached, vec!["A"]);
+        assert_eq!(report.activation[0].step, None);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_report_minimality() {
+        let pathway = chain_pathway();
+        let seeds = HashSet::from([0, 1]);
+        let mut report = Report::new(&pathway, &seeds, &pathway.closure(&seeds));
+        assert!(report.passed());
+
+        report.set_minimality(
+            &pathway,
+            &MinimalityReport {
+                redundant: vec![1],
+                swaps: vec![SeedSwap {
+                    removed: 0,
+                    added: 2,
+                    redundant: 1,
+                }],
+            },
+        );
+        let minimality = report.minimality.as_ref().unwrap();
+        assert!(!minimality.minimal);
+        assert_eq!(minimality.redundant, vec!["B"]);
+        assert_eq!(minimality.swaps[0].removed, "A");
+        assert_eq!(minimality.swaps[0].added, "C");
+        assert_eq!(minimality.swaps[0].redundant, "B");
+        assert!(!report.passed());
+
+        report.set_minimality(&pathway, &MinimalityReport::default());
+        assert!(report.minimality.as_ref().unwrap().minimal);
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn test_report_time_violations() {
+        let pathway = chain_pathway();
+        let seeds = HashSet::from([0]);
+        let mut report = Report::new(&pathway, &seeds, &pathway.closure(&seeds));
+
+        report.set_time_violations(&pathway, &[]);
+        assert_eq!(report.time_violations.as_ref().map(|v| v.len()), Some(0));
+        assert!(report.passed());
+
+        report.set_time_violations(
+            &pathway,
+            &[TimeViolation {
+                compound: 2,
+                claimed: 1,
+                earliest: Some(2),
+            }],
+        );
+        let violation = &report.time_violations.as_ref().unwrap()[0];
+        assert_eq!(violation.compound, "C");
+        assert_eq!(violation.claimed, 1);
+        assert_eq!(violation.earliest, Some(2));
+        assert!(!report.passed());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["time_violations"][0]["compound"], "C");
+        assert!(json.get("minimality").is_none());
+    }
+}
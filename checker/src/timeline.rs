@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+use log::{info, warn};
+use msstools::formats::graph::{write_graph, GraphFormat, Overlay};
+use msstools::pw::Pathway;
+use serde::Serialize;
+
+use crate::report::CompoundActivation;
+
+/// Row of the timeline
+#[derive(Debug, Serialize)]
+struct TimelineRow<'a> {
+    step: u32,
+    compound: &'a str,
+
+    /// Empty for the seeds
+    reaction: Option<&'a str>,
+}
+
+/// Writes the reached compounds ordered by step, as JSON if the file
+/// extension is .json and as CSV otherwise
+pub fn write_timeline(activation: &[CompoundActivation], path: &Path) {
+    let mut rows: Vec<TimelineRow> = activation
+        .iter()
+        .filter_map(|a| {
+            Some(TimelineRow {
+                step: a.step?,
+                compound: &a.compound,
+                reaction: a.reaction.as_deref(),
+            })
+        })
+        .collect();
+    rows.sort_by_key(|r| (r.step, r.compound));
+
+    info!("Writing timeline to {}", path.display());
+
+    if path.extension().is_some_and(|e| e == "json") {
+        let file = File::create(path).expect("Can't create timeline");
+        serde_json::to_writer_pretty(file, &rows).expect("Can't write timeline");
+        return;
+    }
+
+    let mut writer = csv::Writer::from_path(path).expect("Can't create timeline");
+    for row in rows {
+        writer.serialize(row).expect("Can't write timeline");
+    }
+    writer.flush().expect("Can't write timeline");
+}
+
+/// Writes one DOT frame per closure step (frame_000.dot is the seed set)
+/// and, if asked, renders them to SVG with Graphviz
+pub fn write_frames(pw: &Pathway, seeds: &HashSet<u32>, dir: &Path, svg: bool) {
+    fs::create_dir_all(dir).expect("Can't create the frames directory");
+
+    let overlay = Overlay::new(pw, seeds);
+    let steps = pw.closure(seeds).get_steps();
+    info!("Writing {} frames to {}", steps + 1, dir.display());
+
+    let mut render = svg;
+    for step in 0..=steps {
+        let frame = dir.join(format!("frame_{:03}.dot", step));
+        write_graph(pw, Some(&overlay.until(step)), GraphFormat::Dot, &frame)
+            .expect("Can't write frame");
+
+        if !render {
+            continue;
+        }
+
+        let status = Command::new("dot")
+            .arg("-Tsvg")
+            .arg("-o")
+            .arg(frame.with_extension("svg"))
+            .arg(&frame)
+            .status();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => warn!("Graphviz failed on {}: {}", frame.display(), status),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                warn!("Graphviz (dot) not found, only the DOT frames are written");
+                render = false;
+            }
+            Err(e) => panic!("Can't run Graphviz: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::PathBuf;
+
+    use msstools::pw::Pathway;
+
+    use super::*;
+    use crate::report::tests::chain_pathway;
+    use crate::report::Report;
+
+    fn output_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("checker-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chain_report(pathway: &Pathway, seeds: &HashSet<u32>) -> Report {
+        Report::new(pathway, seeds, &pathway.closure(seeds))
+    }
+
+    #[test]
+    fn test_write_timeline_csv() {
+        let pathway = chain_pathway();
+        let report = chain_report(&pathway, &HashSet::from([0]));
+
+        let dir = output_dir("timeline-csv");
+        let path = dir.join("timeline.csv");
+        write_timeline(&report.activation, &path);
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(text, "step,compound,reaction\n0,A,\n1,B,R0\n2,C,R1\n");
+    }
+
+    #[test]
+    fn test_write_timeline_json() {
+        let pathway = chain_pathway();
+        // C is not reached and is left out
+        let report = chain_report(&pathway, &HashSet::from([1]));
+
+        let dir = output_dir("timeline-json");
+        let path = dir.join("timeline.json");
+        write_timeline(&report.activation, &path);
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"step": 0, "compound": "B", "reaction": null},
+                {"step": 1, "compound": "C", "reaction": "R1"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let pathway = chain_pathway();
+        let seeds = HashSet::from([0]);
+        let steps = pathway.closure(&seeds).get_steps();
+
+        let dir = output_dir("frames");
+        write_frames(&pathway, &seeds, &dir, false);
+        let mut frames: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        frames.sort();
+        let first = fs::read_to_string(dir.join("frame_000.dot")).unwrap();
+        let last = fs::read_to_string(dir.join(&frames[frames.len() - 1])).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // one frame for the seeds and one for every closure step
+        assert_eq!(frames.len(), steps as usize + 1);
+        assert_eq!(
+            frames,
+            vec!["frame_000.dot", "frame_001.dot", "frame_002.dot"]
+        );
+
+        assert!(first.contains("c0 [label=\"A\", shape=ellipse, seed=true"));
+        assert!(first.contains("c2 [label=\"C\", shape=ellipse, style=dashed]"));
+        assert!(last.contains("c2 [label=\"C\", shape=ellipse, step=2"));
+        assert!(!last.contains("dashed"));
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Component, Path, PathBuf};
+
+use log::{info, warn};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::{generate, prepare_pathway, read_pathway, BatchArgs, ModelType, OutputFormat};
+
+/// A pathway of the batch
+#[derive(Debug)]
+struct Instance {
+    /// Path relative to the batch root, without extension
+    name: String,
+    input: PathBuf,
+}
+
+/// Row of the manifest
+#[derive(Debug, Serialize)]
+struct ManifestRow {
+    instance: String,
+    input: String,
+    compounds_pre: Option<usize>,
+    reactions_pre: Option<usize>,
+    compounds_post: Option<usize>,
+    reactions_post: Option<usize>,
+    model: Option<String>,
+    error: Option<String>,
+}
+
+/// Generates the model of every pathway and writes the manifest, returns
+/// true if no instance failed
+pub fn run(inputs: &str, config: &Config, args: &BatchArgs) -> bool {
+    let mode = config.model.expect("No model type given");
+    if mode == ModelType::Acyclic {
+        panic!("The acyclic model is exact only with --solve, which the batch mode doesn't run");
+    }
+    if config.solve.is_some() {
+        warn!("The batch mode doesn't solve the models, the solver is ignored");
+    }
+
+    let instances = find_pathways(inputs);
+    info!("Generating {} instances", instances.len());
+
+    fs::create_dir_all(&args.output_dir).expect("Can't create the output directory");
+
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(jobs) = args.jobs {
+        pool = pool.num_threads(jobs);
+    }
+    let pool = pool.build().expect("Can't start the thread pool");
+
+    let extension = match mode {
+        ModelType::Maxsat => "wcnf",
+        ModelType::Asp => "lp",
+        _ => match config.output_format {
+            Some(OutputFormat::Mps) | Some(OutputFormat::FreeMps) => "mps",
+            _ => "lp",
+        },
+    };
+
+    let rows: Vec<ManifestRow> = pool.install(|| {
+        instances
+            .par_iter()
+            .map(|instance| generate_instance(instance, config, args, extension))
+            .collect()
+    });
+
+    let manifest = args
+        .manifest
+        .clone()
+        .unwrap_or_else(|| args.output_dir.join("manifest.csv"));
+    info!("Writing manifest to {}", manifest.display());
+    write_manifest(&rows, &manifest);
+
+    rows.iter().all(|r| r.error.is_none())
+}
+
+fn generate_instance(
+    instance: &Instance,
+    config: &Config,
+    args: &BatchArgs,
+    extension: &str,
+) -> ManifestRow {
+    info!("Generating {}", instance.name);
+
+    let dir = args.output_dir.join(&instance.name);
+    let model = dir.join(format!("model.{}", extension));
+
+    let mut config = config.clone();
+    config.input = Some(instance.input.clone());
+    config.output = Some(model.clone());
+    config.json_model_pre = Some(dir.join("pre.json"));
+    config.json_model_post = Some(dir.join("post.json"));
+    config.contraction_map = config
+        .preprocessing
+        .as_ref()
+        .is_some_and(|p| p.contains('e'))
+        .then(|| dir.join("contraction.json"));
+    config.mapping = None;
+    config.var_map = args.var_map.then(|| dir.join("var.map.json"));
+    config.solve = None;
+
+    // the generator panics on malformed input
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        fs::create_dir_all(&dir).expect("Can't create the instance directory");
+
+        let mut pathway = read_pathway(&config);
+        let pre = (pathway.get_compounds_count(), pathway.get_reactions_count());
+        prepare_pathway(&mut pathway, &config);
+        let post = (pathway.get_compounds_count(), pathway.get_reactions_count());
+
+        generate(&pathway, &config);
+        (pre, post)
+    }));
+
+    let mut row = ManifestRow {
+        instance: instance.name.clone(),
+        input: instance.input.to_string_lossy().into_owned(),
+        compounds_pre: None,
+        reactions_pre: None,
+        compounds_post: None,
+        reactions_post: None,
+        model: None,
+        error: None,
+    };
+
+    match result {
+        Ok((pre, post)) => {
+            (row.compounds_pre, row.reactions_pre) = (Some(pre.0), Some(pre.1));
+            (row.compounds_post, row.reactions_post) = (Some(post.0), Some(post.1));
+            row.model = Some(model.to_string_lossy().into_owned());
+        }
+        Err(payload) => {
+            let error = payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "unknown error".to_string());
+            warn!("Instance {} failed: {}", instance.name, error);
+            row.error = Some(error);
+        }
+    }
+    row
+}
+
+/// Every file of the directory (hidden ones excluded), or the files matching
+/// the glob pattern. The instance names are relative to the directory or to
+/// the part of the pattern before the first wildcard, without the extension:
+/// two inputs that only differ in the extension are refused.
+fn find_pathways(inputs: &str) -> Vec<Instance> {
+    let path = Path::new(inputs);
+
+    let (base, mut files): (PathBuf, Vec<PathBuf>) = if path.is_dir() {
+        let files = fs::read_dir(path)
+            .expect("Can't read the batch directory")
+            .map(|e| e.expect("Can't read the batch directory").path())
+            .filter(|p| p.is_file())
+            .filter(|p| !p.file_name().unwrap().to_string_lossy().starts_with('.'))
+            .collect();
+        (path.to_path_buf(), files)
+    } else {
+        let files = glob::glob(inputs)
+            .expect("Invalid glob pattern")
+            .map(|p| p.expect("Can't read a matched path"))
+            .filter(|p| p.is_file())
+            .collect();
+        let base = path
+            .components()
+            .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+            .collect();
+        (base, files)
+    };
+    files.sort();
+
+    if files.is_empty() {
+        warn!("No pathway matches {}", inputs);
+    }
+
+    let instances: Vec<Instance> = files
+        .into_iter()
+        .map(|input| {
+            let relative = input.strip_prefix(&base).unwrap_or(&input);
+            let name = relative
+                .with_extension("")
+                .components()
+                .filter(|c| matches!(c, Component::Normal(_)))
+                .collect::<PathBuf>();
+            Instance {
+                name: name.to_string_lossy().into_owned(),
+                input,
+            }
+        })
+        .collect();
+
+    // foo.txt and foo.pddl would write the same output directory
+    let mut names: HashMap<&str, &Path> = HashMap::new();
+    for instance in &instances {
+        if let Some(other) = names.insert(&instance.name, &instance.input) {
+            panic!(
+                "{} and {} are both instance {}, rename one of them",
+                other.display(),
+                instance.input.display(),
+                instance.name
+            );
+        }
+    }
+
+    instances
+}
+
+fn write_manifest(rows: &[ManifestRow], path: &Path) {
+    if path.extension().is_some_and(|e| e == "json") {
+        let file = File::create(path).expect("Can't create manifest");
+        serde_json::to_writer_pretty(file, rows).expect("Can't write manifest");
+        return;
+    }
+
+    let mut writer = csv::Writer::from_path(path).expect("Can't create manifest");
+    for row in rows {
+        writer.serialize(row).expect("Can't write manifest");
+    }
+    writer.flush().expect("Can't write manifest");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct TestArgs {
+        #[command(flatten)]
+        batch: BatchArgs,
+    }
+
+    fn batch_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("generator-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (file, text) in files {
+            let path = dir.join("pathways").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+        dir
+    }
+
+    /// A -> B -> C
+    const CHAIN: &str = "3\nA\nB\nC\n2\nR1\n1\nA\n1\nB\nR2\n1\nB\n1\nC\n";
+
+    #[test]
+    fn test_find_pathways() {
+        let dir = batch_dir(
+            "find",
+            &[
+                ("ecoli.txt", CHAIN),
+                ("plants/yeast.txt", CHAIN),
+                (".hidden", ""),
+            ],
+        );
+        let pathways = dir.join("pathways");
+
+        let flat = find_pathways(pathways.to_str().unwrap());
+        let pattern = pathways.join("*/*.txt");
+        let nested = find_pathways(pattern.to_str().unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names = |instances: &[Instance]| {
+            instances
+                .iter()
+                .map(|i| i.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(names(&flat), vec!["ecoli"]);
+        assert_eq!(names(&nested), vec!["plants/yeast"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "are both instance foo")]
+    fn test_find_pathways_collision() {
+        let dir = batch_dir("collision", &[("foo.txt", CHAIN), ("foo.pddl", "")]);
+        find_pathways(dir.join("pathways").to_str().unwrap());
+    }
+
+    #[test]
+    fn test_manifest() {
+        let dir = batch_dir(
+            "manifest",
+            &[("chain.txt", CHAIN), ("broken.txt", "2\nA\n")],
+        );
+        let output_dir = dir.join("out");
+        let args = TestArgs::parse_from([
+            "generator".as_ref(),
+            "--output-dir".as_ref(),
+            output_dir.as_os_str(),
+            "--var-map".as_ref(),
+        ]);
+        let config = Config {
+            model: Some(ModelType::New),
+            ..Default::default()
+        };
+
+        let passed = run(dir.join("pathways").to_str().unwrap(), &config, &args.batch);
+        let mut reader = csv::Reader::from_path(output_dir.join("manifest.csv")).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        let files: Vec<bool> = ["post.json", "pre.json", "model.lp", "var.map.json"]
+            .iter()
+            .map(|f| output_dir.join("chain").join(f).is_file())
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // the broken pathway fails alone
+        assert!(!passed);
+        assert_eq!(files, vec![true; 4]);
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(&rows[0][0], "broken");
+        assert_eq!(&rows[0][6], "");
+        assert!(!rows[0][7].is_empty());
+
+        assert_eq!(&rows[1][0], "chain");
+        let sizes: Vec<&str> = rows[1].iter().skip(2).take(4).collect();
+        assert_eq!(sizes, vec!["3", "2", "3", "2"]);
+        assert!(rows[1][6].ends_with("model.lp"));
+        assert_eq!(&rows[1][7], "");
+    }
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{InputArgs, InputType, ModelArgs, ModelType, OutputFormat, SolverType};
+
+/// Experiment definition, read from a TOML or YAML file.
+///
+/// Every value given on the command line overrides the one in the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Pathway to read
+    pub input: Option<PathBuf>,
+    pub input_type: Option<InputType>,
+
+    /// Preprocessing pipeline (see `--preprocessing`)
+    pub preprocessing: Option<String>,
+    pub currency: Option<PathBuf>,
+    pub currency_degree: Option<usize>,
+    pub contraction_map: Option<PathBuf>,
+
+    pub model: Option<ModelType>,
+
+    /// T/M of the model
+    #[serde(alias = "time")]
+    pub horizon: Option<i32>,
+
+    /// Model file
+    pub output: Option<PathBuf>,
+    pub output_format: Option<OutputFormat>,
+
+    pub json_model_pre: Option<PathBuf>,
+    pub json_model_post: Option<PathBuf>,
+    pub mapping: Option<PathBuf>,
+    pub var_map: Option<PathBuf>,
+
+    pub solve: Option<SolverType>,
+    pub time_limit: Option<u32>,
+    pub threads: Option<u32>,
+    pub solver_command: Option<String>,
+}
+
+impl Config {
+    /// Reads the config file, YAML if the extension is .yaml or .yml and
+    /// TOML otherwise. Relative paths start from the config directory.
+    pub fn load(path: &Path) -> Self {
+        let text = fs::read_to_string(path).expect("Can't read config file");
+
+        let mut config: Config = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&text).expect("Error parsing yaml"),
+            _ => toml::from_str(&text).expect("Error parsing toml"),
+        };
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for file in [
+            &mut config.input,
+            &mut config.currency,
+            &mut config.contraction_map,
+            &mut config.output,
+            &mut config.json_model_pre,
+            &mut config.json_model_post,
+            &mut config.mapping,
+            &mut config.var_map,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *file = base.join(&*file);
+        }
+
+        config
+    }
+
+    /// Config given with --config (if any) overridden by the input arguments
+    pub fn from_input_args(args: &InputArgs) -> Self {
+        let mut config = args.config.as_deref().map(Config::load).unwrap_or_default();
+
+        override_with(&mut config.input, &args.input);
+        override_with(&mut config.input_type, &args.input_type);
+        override_with(&mut config.preprocessing, &args.preprocessing);
+        override_with(&mut config.currency, &args.currency);
+        override_with(&mut config.currency_degree, &args.currency_degree);
+        override_with(&mut config.contraction_map, &args.contraction_map);
+        override_with(&mut config.json_model_pre, &args.json_model_pre);
+        override_with(&mut config.json_model_post, &args.json_model_post);
+
+        config
+    }
+
+    pub fn override_model(&mut self, args: &ModelArgs) {
+        override_with(&mut self.model, &args.model);
+        override_with(&mut self.horizon, &args.time);
+        override_with(&mut self.output_format, &args.output_format);
+    }
+}
+
+pub fn override_with<T: Clone>(value: &mut Option<T>, arg: &Option<T>) {
+    if arg.is_some() {
+        value.clone_from(arg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use clap::Parser;
+
+    use super::*;
+
+    /// The arguments shared by the subcommands that read a config
+    #[derive(Parser)]
+    struct TestArgs {
+        #[command(flatten)]
+        input: InputArgs,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    }
+
+    fn config_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("generator-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn load(name: &str, file: &str, text: &str) -> (Config, PathBuf) {
+        let dir = config_dir(name);
+        let path = dir.join(file);
+        fs::write(&path, text).unwrap();
+        let config = Config::load(&path);
+        fs::remove_dir_all(&dir).unwrap();
+        (config, dir)
+    }
+
+    #[test]
+    fn test_load_toml() {
+        let (config, dir) = load(
+            "toml",
+            "experiment.toml",
+            r#"
+input = "pathways/ecoli.txt"
+input_type = "pddl"
+preprocessing = "mPS"
+model = "bigm"
+time = 20
+output = "/tmp/ecoli.lp"
+output_format = "free-mps"
+solve = "highs"
+threads = 4
+"#,
+        );
+
+        assert_eq!(config.input, Some(dir.join("pathways/ecoli.txt")));
+        assert_eq!(config.input_type, Some(InputType::Pddl));
+        assert_eq!(config.preprocessing.as_deref(), Some("mPS"));
+        assert_eq!(config.model, Some(ModelType::Bigm));
+        assert_eq!(config.horizon, Some(20));
+        // absolute paths are kept
+        assert_eq!(config.output, Some(PathBuf::from("/tmp/ecoli.lp")));
+        assert_eq!(config.output_format, Some(OutputFormat::FreeMps));
+        assert_eq!(config.solve, Some(SolverType::Highs));
+        assert_eq!(config.threads, Some(4));
+        assert_eq!(config.time_limit, None);
+        assert_eq!(config.var_map, None);
+    }
+
+    #[test]
+    fn test_load_yaml() {
+        for file in ["experiment.yaml", "experiment.yml"] {
+            let (config, dir) = load(
+                "yaml",
+                file,
+                "input: ecoli.txt\nmodel: timeset\nhorizon: -1\nvar_map: out/var.map.json\n",
+            );
+
+            assert_eq!(config.input, Some(dir.join("ecoli.txt")));
+            assert_eq!(config.model, Some(ModelType::Timeset));
+            assert_eq!(config.horizon, Some(-1));
+            assert_eq!(config.var_map, Some(dir.join("out/var.map.json")));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Error parsing toml")]
+    fn test_load_unknown_field() {
+        load(
+            "unknown",
+            "experiment.toml",
+            "model = \"bigm\"\nhorizont = 20\n",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error parsing yaml")]
+    fn test_load_unknown_field_yaml() {
+        load(
+            "unknown-yaml",
+            "experiment.yaml",
+            "model: bigm\nsolver: cbc\n",
+        );
+    }
+
+    #[test]
+    fn test_override_with() {
+        let mut value = Some(1);
+        override_with(&mut value, &None);
+        assert_eq!(value, Some(1));
+        override_with(&mut value, &Some(2));
+        assert_eq!(value, Some(2));
+
+        let mut value = None;
+        override_with(&mut value, &Some("lp"));
+        assert_eq!(value, Some("lp"));
+    }
+
+    #[test]
+    fn test_arguments_override_config() {
+        let dir = config_dir("override");
+        let path = dir.join("experiment.toml");
+        fs::write(
+            &path,
+            "input = \"ecoli.txt\"\npreprocessing = \"m\"\nmodel = \"bigm\"\ntime = 20\n",
+        )
+        .unwrap();
+
+        let args = TestArgs::parse_from([
+            "generator".as_ref(),
+            "--config".as_ref(),
+            path.as_os_str(),
+            "--preprocessing".as_ref(),
+            "mPS".as_ref(),
+            "--model".as_ref(),
+            "new".as_ref(),
+        ]);
+        let mut config = Config::from_input_args(&args.input);
+        config.override_model(&args.model);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.input, Some(dir.join("ecoli.txt")));
+        assert_eq!(config.preprocessing.as_deref(), Some("mPS"));
+        assert_eq!(config.model, Some(ModelType::New));
+        assert_eq!(config.horizon, Some(20));
+    }
+}
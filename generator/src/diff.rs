@@ -0,0 +1,80 @@
+use std::io::{Result, Write};
+
+use msstools::pw::{DiffReaction, PathwayDiff, SeedSetDiff};
+
+/// "name: A + B -> C", since reaction names are not unique
+fn reaction_line(reaction: &DiffReaction) -> String {
+    format!(
+        "{}: {} -> {}",
+        reaction.name,
+        reaction.substrates.join(" + "),
+        reaction.products.join(" + ")
+    )
+}
+
+/// Writes the differences one per line: "+"/"-" for added/removed compounds
+/// and reactions, "~" for the reactions whose sides changed
+pub fn write_pathway_diff<W: Write>(diff: &PathwayDiff, writer: &mut W) -> Result<()> {
+    for compound in &diff.added_compounds {
+        writeln!(writer, "+ compound {}", compound)?;
+    }
+    for compound in &diff.removed_compounds {
+        writeln!(writer, "- compound {}", compound)?;
+    }
+    for reaction in &diff.added_reactions {
+        writeln!(writer, "+ reaction {}", reaction_line(reaction))?;
+    }
+    for reaction in &diff.removed_reactions {
+        writeln!(writer, "- reaction {}", reaction_line(reaction))?;
+    }
+
+    for change in &diff.changed_reactions {
+        let side = |added: &[String], removed: &[String]| -> String {
+            added
+                .iter()
+                .map(|c| format!("+{}", c))
+                .chain(removed.iter().map(|c| format!("-{}", c)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut parts = vec![];
+        let substrates = side(&change.added_substrates, &change.removed_substrates);
+        if !substrates.is_empty() {
+            parts.push(format!("substrates {}", substrates));
+        }
+        let products = side(&change.added_products, &change.removed_products);
+        if !products.is_empty() {
+            parts.push(format!("products {}", products));
+        }
+        writeln!(
+            writer,
+            "~ reaction {}: {}",
+            change.reaction,
+            parts.join(", ")
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_seed_diff<W: Write>(diff: &SeedSetDiff, writer: &mut W) -> Result<()> {
+    writeln!(writer, "Common seeds: {}", diff.common.join(", "))?;
+    writeln!(writer, "Only in first: {}", diff.only_first.join(", "))?;
+    writeln!(writer, "Only in second: {}", diff.only_second.join(", "))?;
+    writeln!(
+        writer,
+        "Reached compounds: {} (first), {} (second)",
+        diff.first_reached, diff.second_reached
+    )?;
+    writeln!(
+        writer,
+        "Reached only by first: {}",
+        diff.reached_only_first.join(", ")
+    )?;
+    writeln!(
+        writer,
+        "Reached only by second: {}",
+        diff.reached_only_second.join(", ")
+    )
+}
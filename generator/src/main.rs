@@ -1,59 +1,174 @@
+mod batch;
+mod config;
+mod diff;
+mod stats;
+
 use std::cmp::min;
-use std::fs::File;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
-use clap::ValueEnum;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::{override_with, Config};
 use log::info;
 use log::trace;
+use log::warn;
 use lp_modeler::format::lp_format::LpFileFormat;
-use msstools::models::bigmmodel::build_bigm_model;
-use msstools::models::newmodel::build_newmodel_model;
-use msstools::models::timesetmodel::build_timeset_model;
+use msstools::encoders::asp::write_asp;
+use msstools::encoders::wcnf::encode_wcnf;
+use msstools::formats::graph::{write_graph, write_node_table, GraphFormat, Overlay};
+use msstools::formats::mps::MpsFileFormat;
+use msstools::formats::readable::write_readable;
+use msstools::formats::ModelFormat;
+use msstools::models::acyclicmodel::AcyclicModel;
+use msstools::models::bigmmodel::BigMModel;
+use msstools::models::mssmodel::{ModelVariable, MssModel};
+use msstools::models::newmodel::NewModel;
+use msstools::models::timesetmodel::TimeSetModel;
+use msstools::parsers::parsejson::parse_json;
 use msstools::parsers::parsepddl::parse_pddl;
 use msstools::parsers::parsereadable::parse_readable;
-use msstools::pw::Pathway;
+use msstools::pw::{
+    diff_pathways, diff_seed_sets, merge_community, CommunityOptions, ContractionMap, Pathway,
+};
+use msstools::solutions::{
+    parse_seed_list, seed_variable_map, write_variable_map, DEFAULT_TOLERANCE,
+};
+use msstools::solvers::{solver_runner, SolverKind, SolverOptions, Status};
+use serde::Deserialize;
 use serde_json::to_writer_pretty;
+use stats::StatsReport;
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum ModelType {
     Timeset,
     Bigm,
     New,
+    /// Cycle-elimination cuts, exact only with --solve
+    Acyclic,
+    Maxsat,
+    Asp,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum InputType {
     Readable,
-    PDDL,
+    Pddl,
+    /// Pathway exported by the generator
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum OutputFormat {
+    Lp,
+    /// Fixed MPS, only for models whose names fit in 8 characters
+    Mps,
+    FreeMps,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum SolverType {
+    Cbc,
+    Glpk,
+    Highs,
+    Scip,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PathwayFormat {
+    Json,
+    Readable,
+    /// Graphviz bipartite graph
+    Dot,
+    Graphml,
+    /// Cytoscape edges between node ids, with the names and attributes in
+    /// <output>.nodes.tsv
+    Sif,
 }
 
 #[derive(Parser)]
-struct Args {
-    /// Generated model type
-    mode: ModelType,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    /// Name of the file to read
-    filename: PathBuf,
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the model (and optionally solve it)
+    Generate(GenerateArgs),
 
-    /// T/M of the model
-    #[arg(long, short, default_value_t = 10)]
-    time: i32,
+    /// Preprocess the pathway and write it as json
+    Preprocess(PreprocessArgs),
 
-    /// Name of the output file
-    model_name: PathBuf,
+    /// Convert the pathway to another format
+    Convert(ConvertArgs),
+
+    /// Print pathway statistics
+    Stats(StatsArgs),
+
+    /// Generate the models of many pathways in parallel
+    Batch(BatchArgs),
+
+    /// Compare two pathways by compound and reaction name (exit code 1 if
+    /// they differ)
+    Diff(DiffArgs),
 
-    /// Input file type
-    input_type: InputType,
+    /// Compare two seed sets of the same pathway
+    SeedDiff(SeedDiffArgs),
+
+    /// Merge the pathways of several organisms into a community network
+    Community(CommunityArgs),
+
+    /// Extract a sub-pathway, with renumbered ids and the original names
+    Extract(ExtractArgs),
+}
+
+/// Input pathway and preprocessing, shared by all the subcommands
+#[derive(Args)]
+struct InputArgs {
+    /// Experiment config file (.toml, .yaml or .yml); the other arguments
+    /// override its values
+    #[arg(long, short)]
+    config: Option<PathBuf>,
+
+    /// Name of the file to read
+    #[arg(long, short)]
+    input: Option<PathBuf>,
+
+    /// Input file type (default: readable)
+    #[arg(long)]
+    input_type: Option<InputType>,
 
     /// String representing the preprocessing to execute
     ///
     /// m: merge,
     /// P: remove product-dominated,
     /// S: remove substrate-dominated,
-    /// d: remove duplicated
-    preprocessing_string: Option<String>,
+    /// d: remove duplicated,
+    /// c: remove the currency compounds (see --currency),
+    /// e: contract the equivalent compounds (see --contraction-map)
+    #[arg(long, short)]
+    preprocessing: Option<String>,
+
+    /// Currency compounds (ATP, NADH, H2O...) removed by the c
+    /// preprocessing, one name per line
+    #[arg(long)]
+    currency: Option<PathBuf>,
+
+    /// The c preprocessing also removes the compounds that appear in more
+    /// than this many reactions
+    #[arg(long)]
+    currency_degree: Option<usize>,
+
+    /// Write the compounds contracted by the e preprocessing, with the
+    /// original names of every class, to a json file
+    #[arg(long)]
+    contraction_map: Option<PathBuf>,
 
     /// Export the pathway before the preprocessing to a file
     #[arg(long)]
@@ -64,6 +179,257 @@ struct Args {
     json_model_post: Option<PathBuf>,
 }
 
+/// Model settings, shared by generate and batch
+#[derive(Args)]
+struct ModelArgs {
+    /// Generated model type
+    #[arg(long, short)]
+    model: Option<ModelType>,
+
+    /// T/M of the model (default: 10, -1 for min{#reac, #comp})
+    #[arg(long, short, allow_negative_numbers = true)]
+    time: Option<i32>,
+
+    /// Model file format (default: from the extension of the output file,
+    /// .mps for free MPS and LP otherwise)
+    #[arg(long)]
+    output_format: Option<OutputFormat>,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[command(flatten)]
+    model: ModelArgs,
+
+    /// Name of the output file
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+
+    /// Variable mapping of the MaxSAT encoding (default: <output>.map.json)
+    #[arg(long)]
+    mapping: Option<PathBuf>,
+
+    /// Write a JSON map from seed variable names to compound names, so that
+    /// the checker can read the seeds independently of the compound ids
+    #[arg(long)]
+    var_map: Option<PathBuf>,
+
+    /// Solve the generated model with a local solver and print the seed set
+    #[arg(long)]
+    solve: Option<SolverType>,
+
+    /// Solver time limit in seconds
+    #[arg(long)]
+    time_limit: Option<u32>,
+
+    /// Number of threads used by the solver
+    #[arg(long)]
+    threads: Option<u32>,
+
+    /// Solver executable to use instead of the default one
+    #[arg(long)]
+    solver_command: Option<String>,
+}
+
+#[derive(Args)]
+struct PreprocessArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Json file of the preprocessed pathway
+    #[arg(long, short)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Converted pathway
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// Output format (default: from the extension of the output file,
+    /// readable if unknown)
+    #[arg(long)]
+    to: Option<PathwayFormat>,
+
+    /// Seed list (compound names, one per line) whose closure steps are
+    /// added to the graph nodes
+    #[arg(long)]
+    seeds: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum StatsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Output format; with a preprocessing the statistics before and after
+    /// it are shown side by side
+    #[arg(long, short, default_value = "table")]
+    format: StatsFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum DiffFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Old pathway
+    old: PathBuf,
+
+    /// New pathway
+    new: PathBuf,
+
+    /// Input file type of both pathways (default: json)
+    #[arg(long)]
+    input_type: Option<InputType>,
+
+    /// Output format
+    #[arg(long, short, default_value = "text")]
+    format: DiffFormat,
+}
+
+#[derive(Args)]
+struct SeedDiffArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// First seed list (compound names, one per line)
+    first: PathBuf,
+
+    /// Second seed list
+    second: PathBuf,
+
+    /// Output format
+    #[arg(long, short, default_value = "text")]
+    format: DiffFormat,
+}
+
+#[derive(Args)]
+struct CommunityArgs {
+    /// Pathways of the organisms, as <organism>=<file> or <file> (the
+    /// organism is then the file name without extension)
+    #[arg(required = true)]
+    members: Vec<String>,
+
+    /// Input file type of the pathways (default: readable)
+    #[arg(long)]
+    input_type: Option<InputType>,
+
+    /// Json file of the community pathway
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// Tag the compounds of every organism as <organism>:<compound>; only
+    /// the exchange compounds are shared, through explicit import/export
+    /// reactions
+    #[arg(long)]
+    compartments: bool,
+
+    /// Exchange compounds, one per line (default: the compounds of at least
+    /// two organisms)
+    #[arg(long, requires = "compartments")]
+    exchange: Option<PathBuf>,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false, id = "selection")]
+struct Selection {
+    /// Keep these compounds and the reactions among them
+    #[arg(long, value_delimiter = ',')]
+    compounds: Vec<String>,
+
+    /// Keep these reactions and the compounds they touch
+    #[arg(long, value_delimiter = ',')]
+    reactions: Vec<String>,
+
+    /// Keep the upstream producers of these compounds
+    #[arg(long, value_delimiter = ',')]
+    targets: Vec<String>,
+}
+
+#[derive(Args)]
+struct ExtractArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Comma separated compound or reaction names
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Levels of producers kept above the targets (default: the full
+    /// backward cone)
+    #[arg(long, short = 'k', conflicts_with_all = ["compounds", "reactions"])]
+    steps: Option<u32>,
+
+    /// Extracted pathway (readable, or json if the extension is .json)
+    #[arg(long, short)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// Directory or glob pattern (e.g. "organisms/*/pathway.txt") of the
+    /// pathways (default: the input of the config)
+    inputs: Option<String>,
+
+    /// Experiment config file (.toml, .yaml or .yml); its output paths are
+    /// ignored
+    #[arg(long, short)]
+    config: Option<PathBuf>,
+
+    /// Input file type (default: readable)
+    #[arg(long)]
+    input_type: Option<InputType>,
+
+    /// String representing the preprocessing to execute (see generate)
+    #[arg(long, short)]
+    preprocessing: Option<String>,
+
+    /// Currency compounds removed by the c preprocessing (see generate)
+    #[arg(long)]
+    currency: Option<PathBuf>,
+
+    /// Degree threshold of the c preprocessing (see generate)
+    #[arg(long)]
+    currency_degree: Option<usize>,
+
+    #[command(flatten)]
+    model: ModelArgs,
+
+    /// Output tree, with a <name>/ directory for every pathway
+    #[arg(long, short)]
+    output_dir: PathBuf,
+
+    /// Also write the seed variable map of every model
+    #[arg(long)]
+    var_map: bool,
+
+    /// Manifest of the generated instances (.csv or .json, default:
+    /// <output-dir>/manifest.csv)
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+
+    /// Number of pathways processed in parallel (default: one per core)
+    #[arg(long)]
+    jobs: Option<usize>,
+}
+
 fn print_count(pathway: &Pathway) {
     let cc = pathway.get_compounds_count();
     let rc = pathway.get_reactions_count();
@@ -71,95 +437,542 @@ fn print_count(pathway: &Pathway) {
     info!("Compound count: {}, Reaction count: {}", cc, rc);
 }
 
-fn main() {
-    env_logger::init();
-    let args = Args::parse();
+fn write_json(pathway: &Pathway, json_path: &Path) {
+    let model_out = File::create(json_path).expect("Can't open file");
+    let writer = BufWriter::new(model_out);
+    to_writer_pretty(writer, pathway).expect("Model writing failed");
+}
 
-    let mut pathway = match args.input_type {
-        InputType::Readable => parse_readable(args.filename),
-        InputType::PDDL => parse_pddl(args.filename),
+/// Reads the pathway and applies the preprocessing of the config
+fn load_pathway(config: &Config) -> Pathway {
+    let mut pathway = read_pathway(config);
+    prepare_pathway(&mut pathway, config);
+    pathway
+}
+
+fn read_pathway(config: &Config) -> Pathway {
+    let input = config.input.clone().expect("No input file given");
+
+    let pathway = match config.input_type.unwrap_or(InputType::Readable) {
+        InputType::Readable => parse_readable(input),
+        InputType::Pddl => parse_pddl(input),
+        InputType::Json => parse_json(input),
     };
 
     print_count(&pathway);
+    pathway
+}
 
-    if let Some(json_path) = &args.json_model_pre {
+/// Exports the pre/post json models around the preprocessing
+fn prepare_pathway(pathway: &mut Pathway, config: &Config) {
+    if let Some(json_path) = &config.json_model_pre {
         info!("Writing pre-pp json model to {}", json_path.display());
-        let model_out = File::create(json_path).expect("Can't open file");
-        let writer = BufWriter::new(model_out);
-        to_writer_pretty(writer, &pathway).expect("Model writing failed");
+        write_json(pathway, json_path);
+    }
+
+    info!(
+        "Reactions before preprocessing: {}",
+        &pathway.get_reactions_count()
+    );
+
+    if let Some(pps) = &config.preprocessing {
+        preprocess(pathway, pps, config);
+    }
+
+    info!(
+        "Reactions after preprocessing: {}",
+        &pathway.get_reactions_count()
+    );
+
+    if let Some(json_path2) = &config.json_model_post {
+        info!("Writing post-pp json model to {}", json_path2.display());
+        write_json(pathway, json_path2);
     }
 
-    info!("Reactions before preprocessing: {}", &pathway.get_reactions_count());
-
-    if let Some(pps) = args.preprocessing_string {
-        let mut total_count = 1;
-        let mut cycle_count = 1;
-        while total_count > 0 {
-            total_count = 0;
-            info!("==== Preprocessing cycle #{} ====", cycle_count);
-            cycle_count += 1;
-            for pre in pps.chars() {
-                match pre {
-                    'd' => {
-                        let count = pathway.join_duplicates();
-                        info!("Joining duplicates removed {} reactions", count);
-                        total_count += count;
-                        // print_count(&pathway);
-                    }
-                    'P' => {
-                        let count = pathway.join_dominated_product();
-                        info!("Joining p-dominated removed {} reactions", count);
-                        total_count += count;
-                        // print_count(&pathway);
-                    }
-                    'S' => {
-                        let count = pathway.join_dominated_substrate();
-                        info!("Joining s-dominated removed {} reactions", count);
-                        total_count += count;
-                        // print_count(&pathway);
-                    }
-                    'm' => {
-                        let count = pathway.merge_reactions();
-                        info!("Merged {} reactions", count);
-                        total_count += count;
-                        // print_count(&pathway);
-                    }
-                    _ => {
-                        info!("unknown preprocessing {}", pre);
-                    }
+    trace!("{:?}", pathway);
+}
+
+/// Reads a seed list, with one compound name per line
+fn read_seeds(pathway: &Pathway, path: &Path) -> HashSet<u32> {
+    let text = fs::read_to_string(path).expect("Can't read seed list");
+    parse_seed_list(&text)
+        .iter()
+        .map(|name| {
+            pathway
+                .get_compound_option(name)
+                .unwrap_or_else(|| panic!("Unknown compound {}", name))
+        })
+        .collect()
+}
+
+fn preprocess(pathway: &mut Pathway, pps: &str, config: &Config) {
+    let currency: HashSet<String> = config
+        .currency
+        .as_ref()
+        .map(|path| {
+            let text = fs::read_to_string(path).expect("Can't read currency list");
+            parse_seed_list(&text).into_iter().collect()
+        })
+        .unwrap_or_default();
+    if pps.contains('c') && currency.is_empty() && config.currency_degree.is_none() {
+        warn!("The c preprocessing needs --currency or --currency-degree");
+    }
+
+    let mut contraction = ContractionMap::identity(pathway);
+
+    let mut total_count = 1;
+    let mut cycle_count = 1;
+    while total_count > 0 {
+        total_count = 0;
+        info!("==== Preprocessing cycle #{} ====", cycle_count);
+        cycle_count += 1;
+        for pre in pps.chars() {
+            match pre {
+                'd' => {
+                    let count = pathway.join_duplicates();
+                    info!("Joining duplicates removed {} reactions", count);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                'P' => {
+                    let count = pathway.join_dominated_product();
+                    info!("Joining p-dominated removed {} reactions", count);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                'S' => {
+                    let count = pathway.join_dominated_substrate();
+                    info!("Joining s-dominated removed {} reactions", count);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                'c' => {
+                    let compounds = pathway.currency_compounds(&currency, config.currency_degree);
+                    let count = pathway.remove_compounds(&compounds);
+                    info!("Currency filter removed {} compounds", count);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                'e' => {
+                    let map = pathway.contract_equivalent();
+                    let count = map.contracted_count() as u32;
+                    info!("Contraction removed {} equivalent compounds", count);
+                    contraction = contraction.then(&map);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                'm' => {
+                    let count = pathway.merge_reactions();
+                    info!("Merged {} reactions", count);
+                    total_count += count;
+                    // print_count(&pathway);
+                }
+                _ => {
+                    info!("unknown preprocessing {}", pre);
                 }
             }
         }
     }
 
-    info!("Reactions after preprocessing: {}", &pathway.get_reactions_count());
+    if let Some(map_path) = &config.contraction_map {
+        info!("Writing contraction map to {}", map_path.display());
+        let file = File::create(map_path).expect("Can't open file");
+        to_writer_pretty(BufWriter::new(file), &contraction).expect("Map writing failed");
+    }
+}
 
-    if let Some(json_path2) = args.json_model_post {
-        info!("Writing post-pp json model to {}", json_path2.display());
-        let model_out = File::create(json_path2).expect("Can't open file");
-        let writer = BufWriter::new(model_out);
-        to_writer_pretty(writer, &pathway).expect("Model writing failed");
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Generate(args) => {
+            let mut config = Config::from_input_args(&args.input);
+            config.override_model(&args.model);
+            override_with(&mut config.output, &args.output);
+            override_with(&mut config.mapping, &args.mapping);
+            override_with(&mut config.var_map, &args.var_map);
+            override_with(&mut config.solve, &args.solve);
+            override_with(&mut config.time_limit, &args.time_limit);
+            override_with(&mut config.threads, &args.threads);
+            override_with(&mut config.solver_command, &args.solver_command);
+
+            let pathway = load_pathway(&config);
+            generate(&pathway, &config);
+        }
+        Command::Preprocess(args) => {
+            let config = Config::from_input_args(&args.input);
+            let pathway = load_pathway(&config);
+
+            info!("Writing preprocessed pathway to {}", args.output.display());
+            write_json(&pathway, &args.output);
+        }
+        Command::Convert(args) => {
+            let config = Config::from_input_args(&args.input);
+            let pathway = load_pathway(&config);
+
+            let format =
+                args.to
+                    .unwrap_or_else(|| match args.output.extension().and_then(|e| e.to_str()) {
+                        Some("json") => PathwayFormat::Json,
+                        Some("dot") | Some("gv") => PathwayFormat::Dot,
+                        Some("graphml") => PathwayFormat::Graphml,
+                        Some("sif") => PathwayFormat::Sif,
+                        _ => PathwayFormat::Readable,
+                    });
+
+            let overlay = args
+                .seeds
+                .as_ref()
+                .map(|path| Overlay::new(&pathway, &read_seeds(&pathway, path)));
+            if overlay.is_some() && matches!(format, PathwayFormat::Json | PathwayFormat::Readable)
+            {
+                warn!("The seeds are only shown in the graph formats");
+            }
+
+            info!("Writing pathway to {}", args.output.display());
+            let graph_format = match format {
+                PathwayFormat::Json => return write_json(&pathway, &args.output),
+                PathwayFormat::Readable => {
+                    return write_readable(&pathway, &args.output).expect("Can't write pathway")
+                }
+                PathwayFormat::Dot => GraphFormat::Dot,
+                PathwayFormat::Graphml => GraphFormat::GraphMl,
+                PathwayFormat::Sif => GraphFormat::Sif,
+            };
+            write_graph(&pathway, overlay.as_ref(), graph_format, &args.output)
+                .expect("Can't write graph");
+
+            if graph_format == GraphFormat::Sif {
+                let mut nodes = args.output.clone().into_os_string();
+                nodes.push(".nodes.tsv");
+                info!("Writing node attributes to {}", nodes.to_string_lossy());
+                write_node_table(&pathway, overlay.as_ref(), nodes)
+                    .expect("Can't write node attributes");
+            }
+        }
+        Command::Stats(args) => {
+            let config = Config::from_input_args(&args.input);
+            let mut pathway = read_pathway(&config);
+
+            let before = pathway.stats();
+            prepare_pathway(&mut pathway, &config);
+            let report = StatsReport {
+                before,
+                after: config.preprocessing.is_some().then(|| pathway.stats()),
+            };
+
+            match args.format {
+                StatsFormat::Table => report
+                    .write_table(&mut std::io::stdout())
+                    .expect("Can't write statistics"),
+                StatsFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("Can't write statistics")
+                ),
+            }
+        }
+        Command::Diff(args) => {
+            let read = |input: &PathBuf| {
+                read_pathway(&Config {
+                    input: Some(input.clone()),
+                    input_type: Some(args.input_type.unwrap_or(InputType::Json)),
+                    ..Default::default()
+                })
+            };
+            let pathway_diff = diff_pathways(&read(&args.old), &read(&args.new));
+
+            match args.format {
+                DiffFormat::Text => diff::write_pathway_diff(&pathway_diff, &mut std::io::stdout())
+                    .expect("Can't write diff"),
+                DiffFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&pathway_diff).expect("Can't write diff")
+                ),
+            }
+            std::process::exit(if pathway_diff.is_empty() { 0 } else { 1 });
+        }
+        Command::SeedDiff(args) => {
+            let config = Config::from_input_args(&args.input);
+            let pathway = load_pathway(&config);
+
+            let seed_diff = diff_seed_sets(
+                &pathway,
+                &read_seeds(&pathway, &args.first),
+                &read_seeds(&pathway, &args.second),
+            );
+
+            match args.format {
+                DiffFormat::Text => diff::write_seed_diff(&seed_diff, &mut std::io::stdout())
+                    .expect("Can't write diff"),
+                DiffFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&seed_diff).expect("Can't write diff")
+                ),
+            }
+        }
+        Command::Community(args) => {
+            let pathways: Vec<(String, Pathway)> = args
+                .members
+                .iter()
+                .map(|member| {
+                    let (organism, file) = match member.split_once('=') {
+                        Some((organism, file)) => (organism.to_string(), PathBuf::from(file)),
+                        None => {
+                            let file = PathBuf::from(member);
+                            let stem = file.file_stem().expect("Invalid pathway file");
+                            (stem.to_string_lossy().into_owned(), file)
+                        }
+                    };
+                    info!("Reading organism {} from {}", organism, file.display());
+                    let pathway = read_pathway(&Config {
+                        input: Some(file),
+                        input_type: args.input_type,
+                        ..Default::default()
+                    });
+                    (organism, pathway)
+                })
+                .collect();
+
+            let options = CommunityOptions {
+                compartments: args.compartments,
+                exchange: args.exchange.as_ref().map(|path| {
+                    let text = fs::read_to_string(path).expect("Can't read exchange list");
+                    parse_seed_list(&text).into_iter().collect()
+                }),
+            };
+            let members: Vec<(String, &Pathway)> =
+                pathways.iter().map(|(o, p)| (o.clone(), p)).collect();
+            let community = merge_community(&members, &options);
+
+            print_count(&community);
+            info!("Writing community pathway to {}", args.output.display());
+            write_json(&community, &args.output);
+        }
+        Command::Extract(args) => {
+            let config = Config::from_input_args(&args.input);
+            let pathway = load_pathway(&config);
+
+            let compound_ids = |names: &[String]| -> HashSet<u32> {
+                names
+                    .iter()
+                    .map(|name| {
+                        pathway
+                            .get_compound_option(name)
+                            .unwrap_or_else(|| panic!("Unknown compound {}", name))
+                    })
+                    .collect()
+            };
+
+            let selection = &args.selection;
+            let extracted = if !selection.compounds.is_empty() {
+                pathway.induced_by_compounds(&compound_ids(&selection.compounds))
+            } else if !selection.reactions.is_empty() {
+                let ids = selection
+                    .reactions
+                    .iter()
+                    .map(|name| {
+                        pathway
+                            .get_reactions()
+                            .iter()
+                            .find(|r| r.get_name() == name)
+                            .unwrap_or_else(|| panic!("Unknown reaction {}", name))
+                            .get_id()
+                    })
+                    .collect();
+                pathway.induced_by_reactions(&ids)
+            } else {
+                pathway.upstream(&compound_ids(&selection.targets), args.steps)
+            };
+
+            print_count(&extracted);
+            info!("Writing sub-pathway to {}", args.output.display());
+            match args.output.extension().and_then(|e| e.to_str()) {
+                Some("json") => write_json(&extracted, &args.output),
+                _ => write_readable(&extracted, &args.output).expect("Can't write pathway"),
+            }
+        }
+        Command::Batch(args) => {
+            let mut config = args.config.as_deref().map(Config::load).unwrap_or_default();
+            override_with(&mut config.input_type, &args.input_type);
+            override_with(&mut config.preprocessing, &args.preprocessing);
+            override_with(&mut config.currency, &args.currency);
+            override_with(&mut config.currency_degree, &args.currency_degree);
+            config.override_model(&args.model);
+
+            let inputs = args
+                .inputs
+                .clone()
+                .or_else(|| {
+                    config
+                        .input
+                        .as_ref()
+                        .map(|i| i.to_string_lossy().into_owned())
+                })
+                .expect("No input pathways given");
+
+            let passed = batch::run(&inputs, &config, &args);
+            std::process::exit(if passed { 0 } else { 1 });
+        }
     }
+}
 
-    trace!("{:?}", pathway);
+fn generate(pathway: &Pathway, config: &Config) {
+    let mode = config.model.expect("No model type given");
+    let model_name = config.output.clone().expect("No output file given");
 
-    let mut time_m = args.time;
+    let mut time_m = config.horizon.unwrap_or(10);
 
-    if args.time == -1 {
+    if time_m == -1 {
         info!("Using min{{#read, #comp}} as time instants");
         time_m = min(pathway.get_reactions_count(), pathway.get_compounds_count()) as i32;
     }
 
-    let problem = match args.mode {
-        ModelType::Bigm => build_bigm_model(&pathway, time_m),
-        ModelType::Timeset => build_timeset_model(&pathway, time_m as usize + 2),
-        ModelType::New => build_newmodel_model(&pathway, time_m),
+    if config.solve.is_some() && matches!(mode, ModelType::Asp | ModelType::Maxsat) {
+        warn!("--solve only supports the MIP models, the model will only be exported");
+    }
+
+    if mode == ModelType::Asp {
+        info!("Exporting ASP program");
+        write_asp(pathway, &model_name).expect("Can't write program");
+        return;
+    }
+
+    if mode == ModelType::Maxsat {
+        if let Some(map_path) = &config.var_map {
+            let variables = (0..pathway.get_compounds_count() as u32)
+                .map(|i| (format!("x{}", i), ModelVariable::Seed(i)))
+                .collect();
+            write_var_map(&variables, pathway, map_path);
+        }
+
+        let encoding = encode_wcnf(pathway, time_m as usize + 1);
+
+        info!("Exporting encoding");
+        encoding
+            .write_wcnf(&model_name)
+            .expect("Can't write encoding");
+
+        let mapping_path = config.mapping.clone().unwrap_or_else(|| {
+            let mut name = model_name.into_os_string();
+            name.push(".map.json");
+            PathBuf::from(name)
+        });
+        info!("Writing variable mapping to {}", mapping_path.display());
+        encoding
+            .write_mapping(mapping_path)
+            .expect("Can't write mapping");
+        return;
+    }
+
+    let mut model: Box<dyn MssModel> = match mode {
+        ModelType::Bigm => Box::new(BigMModel::new(pathway, time_m)),
+        ModelType::Timeset => Box::new(TimeSetModel::new(pathway, time_m as usize + 2)),
+        ModelType::New => Box::new(NewModel::new(pathway, time_m)),
+        ModelType::Acyclic => Box::new(AcyclicModel::new(pathway)),
+        ModelType::Maxsat | ModelType::Asp => unreachable!(),
     };
 
-    info!("Exporting model");
+    if model.needs_separation() && config.solve.is_none() {
+        panic!(
+            "The {} model only has the initial cuts and is exact only when solved with --solve",
+            model.name()
+        );
+    }
+
+    if let Some(map_path) = &config.var_map {
+        write_var_map(&model.variables(), pathway, map_path);
+    }
 
-    let binding = args.model_name.into_os_string().into_string().unwrap();
+    let output_format = config.output_format.unwrap_or_else(|| {
+        match model_name.extension().and_then(|e| e.to_str()) {
+            Some("mps") => OutputFormat::FreeMps,
+            _ => OutputFormat::Lp,
+        }
+    });
+
+    let model_format = match output_format {
+        OutputFormat::Lp => ModelFormat::Lp,
+        OutputFormat::Mps => ModelFormat::Mps,
+        OutputFormat::FreeMps => ModelFormat::FreeMps,
+    };
+
+    let binding = model_name.into_os_string().into_string().unwrap();
     let model_path = binding.as_str();
 
-    problem.write_lp(model_path).expect("Can't write model");
+    write_model(model.as_ref(), model_path, model_format);
+
+    let Some(solver) = config.solve else {
+        return;
+    };
+
+    let runner = solver_runner(match solver {
+        SolverType::Cbc => SolverKind::Cbc,
+        SolverType::Glpk => SolverKind::Glpk,
+        SolverType::Highs => SolverKind::Highs,
+        SolverType::Scip => SolverKind::Scip,
+    });
+    let options = SolverOptions {
+        time_limit: config.time_limit,
+        threads: config.threads,
+        command: config.solver_command.clone(),
+    };
+
+    let seeds = loop {
+        let result = runner
+            .run(model_path.as_ref(), model_format, &options)
+            .expect("Solver failed");
+
+        match result.status {
+            Status::Optimal => {}
+            Status::SubOptimal => info!("The solution is not proven optimal"),
+            status => panic!("No solution found: {:?}", status),
+        }
+
+        let solution = model.decode(&result.values, DEFAULT_TOLERANCE);
+
+        let cuts = match model.add_violated_cuts(&solution) {
+            Ok(cuts) => cuts,
+            Err(error) => {
+                warn!("Stopping the separation: {}", error);
+                std::process::exit(1);
+            }
+        };
+        if cuts == 0 {
+            break solution.seeds;
+        }
+
+        info!("Added {} violated cuts, solving again", cuts);
+        write_model(model.as_ref(), model_path, model_format);
+    };
+
+    info!("Seed set size: {}", seeds.len());
+
+    let compounds = pathway.get_compounds();
+    for seed in seeds {
+        println!("{}", compounds[seed as usize].name);
+    }
+}
+
+fn write_var_map(
+    variables: &HashMap<String, ModelVariable>,
+    pathway: &Pathway,
+    map_path: &PathBuf,
+) {
+    info!("Writing variable map to {}", map_path.display());
+    write_variable_map(&seed_variable_map(variables, pathway), map_path)
+        .expect("Can't write variable map");
+}
+
+fn write_model(model: &dyn MssModel, model_path: &str, format: ModelFormat) {
+    let problem = model.build();
+
+    info!("Exporting model");
+
+    match format {
+        ModelFormat::Lp => problem.write_lp(model_path),
+        ModelFormat::Mps => problem.write_mps(model_path, false),
+        ModelFormat::FreeMps => problem.write_mps(model_path, true),
+    }
+    .expect("Can't write model");
 }
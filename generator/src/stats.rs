@@ -0,0 +1,81 @@
+use std::io::{Result, Write};
+
+use msstools::pw::{Distribution, PathwayStats};
+use serde::Serialize;
+
+/// Scalar row of the table
+type Count = fn(&PathwayStats) -> usize;
+
+/// Distribution printed after the table
+type DistributionOf = fn(&PathwayStats) -> &Distribution;
+
+/// Statistics of the pathway, before and after the preprocessing
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub before: PathwayStats,
+
+    /// Only with a preprocessing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<PathwayStats>,
+}
+
+impl StatsReport {
+    pub fn write_table<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let columns: Vec<&PathwayStats> = [Some(&self.before), self.after.as_ref()]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if self.after.is_some() {
+            writeln!(writer, "{:<30}{:>10}{:>10}", "", "before", "after")?;
+        }
+
+        let counts: [(&str, Count); 10] = [
+            ("Compounds", |s| s.compounds),
+            ("Reactions", |s| s.reactions),
+            ("Multi-substrate reactions", |s| s.multi_substrate_reactions),
+            ("Multi-product reactions", |s| s.multi_product_reactions),
+            ("Self-loop reactions", |s| s.self_loop_reactions),
+            ("Compounds without producer", |s| {
+                s.compounds_without_producer
+            }),
+            ("Compounds without consumer", |s| {
+                s.compounds_without_consumer
+            }),
+            ("SCCs", |s| s.scc_sizes.values().sum()),
+            ("Cyclic SCCs", PathwayStats::cyclic_sccs),
+            ("Largest SCC", PathwayStats::largest_scc),
+        ];
+        for (label, count) in counts {
+            write!(writer, "{:<30}", label)?;
+            for stats in &columns {
+                write!(writer, "{:>10}", count(stats))?;
+            }
+            writeln!(writer)?;
+        }
+
+        let distributions: [(&str, DistributionOf); 5] = [
+            ("Compound in-degree", |s| &s.compound_in_degree),
+            ("Compound out-degree", |s| &s.compound_out_degree),
+            ("Reaction substrates", |s| &s.reaction_substrates),
+            ("Reaction products", |s| &s.reaction_products),
+            ("SCC sizes", |s| &s.scc_sizes),
+        ];
+        for (label, distribution) in distributions {
+            writeln!(writer)?;
+            writeln!(writer, "{} (value: count)", label)?;
+            for (stats, name) in columns.iter().zip(["before", "after"]) {
+                if self.after.is_some() {
+                    write!(writer, "  {:<8}", name)?;
+                }
+                let entries: Vec<String> = distribution(stats)
+                    .iter()
+                    .map(|(value, count)| format!("{}: {}", value, count))
+                    .collect();
+                writeln!(writer, "  {}", entries.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+//! Modulo per la codifica del problema in formalismi diversi dalla
+//! programmazione lineare intera
+
+pub mod asp;
+pub mod wcnf;
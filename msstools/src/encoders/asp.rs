@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Result, Write};
+use std::path::Path;
+
+use log::info;
+use serde::Deserialize;
+
+use crate::pw::Pathway;
+
+/// Codifica dei seed set minimi: ogni composto deve essere raggiungibile
+/// dal seed set tramite le reazioni, il numero di seed e' minimizzato
+const MSS_ENCODING: &str = "\
+{ seed(C) } :- compound(C).
+reach(C) :- seed(C).
+reach(P) :- product(P,R), reach(S) : reactant(S,R).
+:- compound(C), not reach(C).
+#minimize { 1,C : seed(C) }.
+#show seed/1.
+";
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(term: &str) -> String {
+    let inner = term
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(term);
+
+    let mut name = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => name.push('\n'),
+                Some(other) => name.push(other),
+                None => {}
+            }
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Scrive il pathway come fatti ASP
+///
+/// I composti sono identificati dal nome, le reazioni dall'ID interno
+/// (i nomi delle reazioni non sono necessariamente unici).
+pub fn write_asp_facts<W: Write>(pathway: &Pathway, writer: &mut W) -> Result<()> {
+    for compound in pathway.get_compounds() {
+        writeln!(writer, "compound({}).", quote(&compound.name))?;
+    }
+
+    let names: Vec<String> = pathway
+        .get_compounds()
+        .iter()
+        .map(|c| quote(&c.name))
+        .collect();
+
+    for reaction in pathway.get_reactions() {
+        let id = reaction.get_id();
+        writeln!(writer, "reaction({}).", id)?;
+        for sub in reaction.get_substrate() {
+            writeln!(writer, "reactant({},{}).", names[*sub as usize], id)?;
+        }
+        for prod in reaction.get_product() {
+            writeln!(writer, "product({},{}).", names[*prod as usize], id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Scrive il programma ASP (fatti e codifica) per clingo
+pub fn write_asp<P: AsRef<Path>>(pathway: &Pathway, path: P) -> Result<()> {
+    info!("Writing ASP program");
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write_asp_facts(pathway, &mut writer)?;
+    writeln!(writer)?;
+    write!(writer, "{}", MSS_ENCODING)?;
+
+    writer.flush()
+}
+
+#[derive(Deserialize)]
+struct ClingoOutput {
+    #[serde(rename = "Call", default)]
+    calls: Vec<ClingoCall>,
+}
+
+#[derive(Deserialize)]
+struct ClingoCall {
+    #[serde(rename = "Witnesses", default)]
+    witnesses: Vec<ClingoWitness>,
+}
+
+#[derive(Deserialize)]
+struct ClingoWitness {
+    #[serde(rename = "Value", default)]
+    value: Vec<String>,
+}
+
+/// Legge l'output JSON di clingo (`--outf=2`) e restituisce i nomi dei
+/// composti nell'ultimo answer set, cioe' quello ottimo
+pub fn parse_clingo_json<R: Read>(reader: R) -> Vec<String> {
+    let output: ClingoOutput = serde_json::from_reader(reader).expect("Error parsing clingo json");
+
+    let witness = output
+        .calls
+        .iter()
+        .flat_map(|call| call.witnesses.iter())
+        .last()
+        .expect("No answer set in clingo output");
+
+    witness
+        .value
+        .iter()
+        .filter_map(|atom| {
+            atom.strip_prefix("seed(")
+                .and_then(|a| a.strip_suffix(')'))
+                .map(unquote)
+        })
+        .collect()
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Result, Write};
+use std::path::Path;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::models::mssmodel::Incidence;
+use crate::pw::Pathway;
+
+/// Associazione tra le variabili DIMACS e i nomi delle variabili del modello
+///
+/// I nomi seguono quelli del modello timeset: x (seed), d (composto
+/// disponibile all'istante), s (reazione attiva all'istante).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WcnfMapping {
+    /// Ultimo istante dell'orizzonte temporale
+    pub horizon: usize,
+
+    /// Nome della variabile DIMACS i nella posizione i - 1
+    pub variables: Vec<String>,
+}
+
+/// Codifica MaxSAT pesata del problema del Minimal Seed Set
+pub struct WcnfEncoding {
+    /// Clausole hard
+    hard: Vec<Vec<i64>>,
+
+    /// Clausole soft con il relativo peso
+    soft: Vec<(u64, Vec<i64>)>,
+
+    mapping: WcnfMapping,
+}
+
+impl WcnfEncoding {
+    pub fn get_mapping(&self) -> &WcnfMapping {
+        &self.mapping
+    }
+
+    pub fn get_hard_count(&self) -> usize {
+        self.hard.len()
+    }
+
+    pub fn get_soft_count(&self) -> usize {
+        self.soft.len()
+    }
+
+    /// Scrive le clausole nel formato DIMACS .wcnf
+    pub fn write_wcnf<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        let top: u64 = self.soft.iter().map(|(w, _)| w).sum::<u64>() + 1;
+
+        writeln!(
+            writer,
+            "p wcnf {} {} {}",
+            self.mapping.variables.len(),
+            self.hard.len() + self.soft.len(),
+            top
+        )?;
+
+        for clause in &self.hard {
+            write!(writer, "{}", top)?;
+            for lit in clause {
+                write!(writer, " {}", lit)?;
+            }
+            writeln!(writer, " 0")?;
+        }
+
+        for (weight, clause) in &self.soft {
+            write!(writer, "{}", weight)?;
+            for lit in clause {
+                write!(writer, " {}", lit)?;
+            }
+            writeln!(writer, " 0")?;
+        }
+
+        writer.flush()
+    }
+
+    /// Scrive l'associazione tra variabili DIMACS e variabili del modello
+    pub fn write_mapping<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, &self.mapping)?;
+        Ok(())
+    }
+}
+
+/// Genera la codifica MaxSAT con istanti 0..=horizon
+///
+/// - x_i <-> d_i0 (la variabile d_i0 coincide con x_i)
+/// - d_iT per ogni composto
+/// - d_it -> d_i(t-1) OR s_j(t-1) per le reazioni j che producono i
+/// - s_jt -> d_kt per i composti k richiesti da j
+/// - soft: NOT x_i con peso 1
+pub fn encode_wcnf(pathway: &Pathway, horizon: usize) -> WcnfEncoding {
+    info!("Building MaxSAT encoding with T = {}", horizon);
+    let incidence = Incidence::new(pathway);
+    let cs = incidence.get_compounds_count();
+    let rs = incidence.get_reactions_count();
+
+    let mut variables = Vec::<String>::new();
+    let mut new_var = |name: String| -> i64 {
+        variables.push(name);
+        variables.len() as i64
+    };
+
+    // vars_d[i][0] is x_i
+    let mut vars_d = Vec::<Vec<i64>>::with_capacity(cs);
+    for i in 0..cs {
+        let mut entry = vec![new_var(format!("x{}", i))];
+        for t in 1..=horizon {
+            entry.push(new_var(format!("d{}_{}", i, t)));
+        }
+        vars_d.push(entry);
+    }
+
+    let mut vars_s = Vec::<Vec<i64>>::with_capacity(rs);
+    for j in 0..rs {
+        vars_s.push(
+            (0..horizon)
+                .map(|t| new_var(format!("s{}_{}", j, t)))
+                .collect(),
+        );
+    }
+
+    let mut hard = Vec::<Vec<i64>>::new();
+    let mut soft = Vec::<(u64, Vec<i64>)>::new();
+
+    for d in &vars_d {
+        hard.push(vec![d[horizon]]);
+        soft.push((1, vec![-d[0]]));
+    }
+
+    for (i, d) in vars_d.iter().enumerate() {
+        for t in 1..=horizon {
+            let mut clause = vec![-d[t], d[t - 1]];
+            for reaction in &incidence.comp_produced_by_reac[i] {
+                clause.push(vars_s[*reaction as usize][t - 1]);
+            }
+            hard.push(clause);
+        }
+    }
+
+    for (j, s) in vars_s.iter().enumerate() {
+        for compound in &incidence.reac_requires_comp[j] {
+            for (t, s_jt) in s.iter().enumerate() {
+                hard.push(vec![-s_jt, vars_d[*compound as usize][t]]);
+            }
+        }
+    }
+
+    info!(
+        "Generated {} variables, {} hard and {} soft clauses",
+        variables.len(),
+        hard.len(),
+        soft.len()
+    );
+
+    WcnfEncoding {
+        hard,
+        soft,
+        mapping: WcnfMapping { horizon, variables },
+    }
+}
+
+/// Legge l'associazione scritta da `WcnfEncoding::write_mapping`
+pub fn read_mapping<P: AsRef<Path>>(path: P) -> WcnfMapping {
+    let file = File::open(path).expect("Can't open mapping file");
+    serde_json::from_reader(std::io::BufReader::new(file)).expect("Error parsing mapping")
+}
+
+/// Legge l'output di un solver MaxSAT e restituisce il valore di ogni
+/// variabile del modello.
+///
+/// Sono supportate sia le righe `v` con letterali (`v 1 -2 3`), anche su
+/// piu' righe, sia il formato a stringa binaria (`v 101`).
+pub fn parse_maxsat_output<R: BufRead>(reader: R, mapping: &WcnfMapping) -> HashMap<String, f64> {
+    let mut values = HashMap::<String, f64>::new();
+
+    for line in reader.lines() {
+        let line = line.expect("Can't read solver output");
+        let Some(assignment) = line.strip_prefix("v ") else {
+            continue;
+        };
+
+        let tokens: Vec<&str> = assignment.split_whitespace().collect();
+        let is_bitstring = tokens.len() == 1
+            && tokens[0].len() > 1
+            && tokens[0].chars().all(|c| c == '0' || c == '1');
+
+        if is_bitstring {
+            for (name, bit) in mapping.variables.iter().zip(tokens[0].chars()) {
+                values.insert(name.clone(), if bit == '1' { 1.0 } else { 0.0 });
+            }
+            continue;
+        }
+
+        for token in tokens {
+            let lit: i64 = token.parse().expect("Invalid literal");
+            if lit == 0 {
+                continue;
+            }
+            if let Some(name) = mapping.variables.get(lit.unsigned_abs() as usize - 1) {
+                values.insert(name.clone(), if lit > 0 { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    values
+}
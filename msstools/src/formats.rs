@@ -0,0 +1,14 @@
+//! Modulo per l'esportazione dei modelli in formati diversi dal .lp e
+//! dei pathway
+
+pub mod graph;
+pub mod mps;
+pub mod readable;
+
+/// Formato del file che contiene il modello
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFormat {
+    Lp,
+    Mps,
+    FreeMps,
+}
@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+
+use crate::pw::Pathway;
+
+/// Formato del grafo bipartito molecole/reazioni
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+    Sif,
+}
+
+/// Seed set e passi della chiusura da riportare come attributi dei nodi
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    seeds: HashSet<u32>,
+
+    /// Passo in cui ogni molecola viene raggiunta (0 per i seed)
+    compound_step: Vec<Option<u32>>,
+
+    /// Passo in cui ogni reazione si attiva per la prima volta
+    reaction_step: Vec<Option<u32>>,
+
+    /// Ultimo passo mostrato, i cui nodi vengono evidenziati (solo per i
+    /// fotogrammi della chiusura)
+    frame: Option<u32>,
+}
+
+impl Overlay {
+    pub fn new(pathway: &Pathway, seeds: &HashSet<u32>) -> Self {
+        let closure = pathway.closure(seeds);
+
+        let compound_step = (0..pathway.get_compounds_count() as u32)
+            .map(|c| closure.get_activation(c))
+            .collect::<Vec<_>>();
+
+        // una reazione si attiva il passo dopo l'ultimo dei suoi reagenti
+        let reaction_step = pathway
+            .get_reactions()
+            .iter()
+            .map(|r| {
+                r.get_substrate()
+                    .iter()
+                    .map(|s| compound_step[*s as usize])
+                    .try_fold(0, |step, s| s.map(|s| step.max(s)))
+                    .map(|step| step + 1)
+            })
+            .collect();
+
+        Overlay {
+            seeds: seeds.clone(),
+            compound_step,
+            reaction_step,
+            frame: None,
+        }
+    }
+
+    /// Chiusura fermata al passo dato: i nodi dei passi successivi risultano
+    /// non raggiunti
+    pub fn until(&self, step: u32) -> Self {
+        let cut =
+            |steps: &Vec<Option<u32>>| steps.iter().map(|s| s.filter(|s| *s <= step)).collect();
+        Overlay {
+            seeds: self.seeds.clone(),
+            compound_step: cut(&self.compound_step),
+            reaction_step: cut(&self.reaction_step),
+            frame: Some(step),
+        }
+    }
+
+    pub fn is_seed(&self, compound: u32) -> bool {
+        self.seeds.contains(&compound)
+    }
+
+    pub fn compound_step(&self, compound: u32) -> Option<u32> {
+        self.compound_step[compound as usize]
+    }
+
+    pub fn reaction_step(&self, reaction: u32) -> Option<u32> {
+        self.reaction_step[reaction as usize]
+    }
+
+    /// Il nodo e' stato raggiunto nell'ultimo passo del fotogramma
+    pub fn is_latest(&self, step: Option<u32>) -> bool {
+        self.frame.is_some() && step == self.frame
+    }
+}
+
+/// Scrive il pathway nel formato scelto; per SIF vengono scritti solo gli
+/// archi (vedi `write_node_table_to` per gli attributi)
+pub fn write_graph<P: AsRef<Path>>(
+    pathway: &Pathway,
+    overlay: Option<&Overlay>,
+    format: GraphFormat,
+    path: P,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        GraphFormat::Dot => write_dot_to(pathway, overlay, &mut writer)?,
+        GraphFormat::GraphMl => write_graphml_to(pathway, overlay, &mut writer)?,
+        GraphFormat::Sif => write_sif_to(pathway, &mut writer)?,
+    }
+    writer.flush()
+}
+
+fn dot_escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Grafo Graphviz: molecole come ellissi e reazioni come rettangoli; con
+/// l'overlay i seed sono colorati, ogni nodo ha l'attributo "step" e nei
+/// fotogrammi i nodi dell'ultimo passo sono evidenziati
+pub fn write_dot_to<W: Write>(
+    pathway: &Pathway,
+    overlay: Option<&Overlay>,
+    writer: &mut W,
+) -> Result<()> {
+    writeln!(writer, "digraph pathway {{")?;
+
+    for compound in pathway.get_compounds() {
+        write!(
+            writer,
+            "  c{} [label=\"{}\", shape=ellipse",
+            compound.id,
+            dot_escape(&compound.name)
+        )?;
+        if let Some(overlay) = overlay {
+            if overlay.is_seed(compound.id) {
+                write!(writer, ", seed=true, style=filled, fillcolor=gold")?;
+            }
+            let step = overlay.compound_step(compound.id);
+            match step {
+                Some(step) => write!(writer, ", step={}", step)?,
+                None => write!(writer, ", style=dashed")?,
+            }
+            if overlay.is_latest(step) {
+                write!(writer, ", color=red, penwidth=2")?;
+            }
+        }
+        writeln!(writer, "];")?;
+    }
+
+    for reaction in pathway.get_reactions() {
+        write!(
+            writer,
+            "  r{} [label=\"{}\", shape=box",
+            reaction.get_id(),
+            dot_escape(reaction.get_name())
+        )?;
+        if let Some(overlay) = overlay {
+            let step = overlay.reaction_step(reaction.get_id());
+            match step {
+                Some(step) => write!(writer, ", step={}", step)?,
+                None => write!(writer, ", style=dashed")?,
+            }
+            if overlay.is_latest(step) {
+                write!(writer, ", color=red, penwidth=2")?;
+            }
+        }
+        writeln!(writer, "];")?;
+    }
+
+    for reaction in pathway.get_reactions() {
+        for sub in reaction.get_substrate() {
+            writeln!(writer, "  c{} -> r{};", sub, reaction.get_id())?;
+        }
+        for prod in reaction.get_product() {
+            writeln!(writer, "  r{} -> c{};", reaction.get_id(), prod)?;
+        }
+    }
+
+    writeln!(writer, "}}")
+}
+
+fn xml_escape(name: &str) -> String {
+    name.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// GraphML con gli attributi "name" e "type" (compound o reaction) e, con
+/// l'overlay, "seed" e "step" (-1 se il nodo non viene raggiunto)
+pub fn write_graphml_to<W: Write>(
+    pathway: &Pathway,
+    overlay: Option<&Overlay>,
+    writer: &mut W,
+) -> Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="type" for="node" attr.name="type" attr.type="string"/>"#
+    )?;
+    if overlay.is_some() {
+        writeln!(
+            writer,
+            r#"  <key id="seed" for="node" attr.name="seed" attr.type="boolean"/>"#
+        )?;
+        writeln!(
+            writer,
+            r#"  <key id="step" for="node" attr.name="step" attr.type="int"/>"#
+        )?;
+    }
+    writeln!(writer, r#"  <graph id="pathway" edgedefault="directed">"#)?;
+
+    let write_node = |writer: &mut W,
+                      id: String,
+                      name: &str,
+                      kind: &str,
+                      seed: bool,
+                      step: Option<u32>|
+     -> Result<()> {
+        writeln!(writer, r#"    <node id="{}">"#, id)?;
+        writeln!(
+            writer,
+            r#"      <data key="name">{}</data>"#,
+            xml_escape(name)
+        )?;
+        writeln!(writer, r#"      <data key="type">{}</data>"#, kind)?;
+        if overlay.is_some() {
+            writeln!(writer, r#"      <data key="seed">{}</data>"#, seed)?;
+            writeln!(
+                writer,
+                r#"      <data key="step">{}</data>"#,
+                step.map_or(-1, i64::from)
+            )?;
+        }
+        writeln!(writer, "    </node>")
+    };
+
+    for compound in pathway.get_compounds() {
+        write_node(
+            writer,
+            format!("c{}", compound.id),
+            &compound.name,
+            "compound",
+            overlay.is_some_and(|o| o.is_seed(compound.id)),
+            overlay.and_then(|o| o.compound_step(compound.id)),
+        )?;
+    }
+    for reaction in pathway.get_reactions() {
+        write_node(
+            writer,
+            format!("r{}", reaction.get_id()),
+            reaction.get_name(),
+            "reaction",
+            false,
+            overlay.and_then(|o| o.reaction_step(reaction.get_id())),
+        )?;
+    }
+
+    for reaction in pathway.get_reactions() {
+        let id = reaction.get_id();
+        for sub in reaction.get_substrate() {
+            writeln!(writer, r#"    <edge source="c{}" target="r{}"/>"#, sub, id)?;
+        }
+        for prod in reaction.get_product() {
+            writeln!(writer, r#"    <edge source="r{}" target="c{}"/>"#, id, prod)?;
+        }
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+/// Cytoscape SIF separato da tabulazioni: "c<id> substrate_of r<id>" e
+/// "r<id> produces c<id>". I nodi sono identificati come in DOT e GraphML
+/// perche' i nomi delle reazioni non sono unici; i nomi si trovano nella
+/// tabella dei nodi.
+pub fn write_sif_to<W: Write>(pathway: &Pathway, writer: &mut W) -> Result<()> {
+    for reaction in pathway.get_reactions() {
+        let id = reaction.get_id();
+        for sub in reaction.get_substrate() {
+            writeln!(writer, "c{}\tsubstrate_of\tr{}", sub, id)?;
+        }
+        for prod in reaction.get_product() {
+            writeln!(writer, "r{}\tproduces\tc{}", id, prod)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_node_table<P: AsRef<Path>>(
+    pathway: &Pathway,
+    overlay: Option<&Overlay>,
+    path: P,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_node_table_to(pathway, overlay, &mut writer)?;
+    writer.flush()
+}
+
+/// Tabella dei nodi del SIF (id, name, type, seed, step) da importare in
+/// Cytoscape; seed e step sono vuoti senza overlay o se il nodo non viene
+/// raggiunto
+pub fn write_node_table_to<W: Write>(
+    pathway: &Pathway,
+    overlay: Option<&Overlay>,
+    writer: &mut W,
+) -> Result<()> {
+    let step = |s: Option<u32>| s.map(|s| s.to_string()).unwrap_or_default();
+
+    writeln!(writer, "id\tname\ttype\tseed\tstep")?;
+    for compound in pathway.get_compounds() {
+        writeln!(
+            writer,
+            "c{}\t{}\tcompound\t{}\t{}",
+            compound.id,
+            compound.name,
+            overlay.map_or(String::new(), |o| o.is_seed(compound.id).to_string()),
+            step(overlay.and_then(|o| o.compound_step(compound.id)))
+        )?;
+    }
+    for reaction in pathway.get_reactions() {
+        writeln!(
+            writer,
+            "r{}\t{}\treaction\t{}\t{}",
+            reaction.get_id(),
+            reaction.get_name(),
+            overlay.map_or(String::new(), |_| "false".to_string()),
+            step(overlay.and_then(|o| o.reaction_step(reaction.get_id())))
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{Error, ErrorKind, Result};
+
+use lp_modeler::dsl::{Constraint, LpExpression, LpObjective, LpProblem};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Binary,
+    Integer,
+    Continuous,
+}
+
+/// Variabile del problema, con i limiti dichiarati in lp-modeler
+struct Column {
+    name: String,
+    kind: Kind,
+    lower: Option<f64>,
+    upper: Option<f64>,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+/// Colonne nell'ordine in cui compaiono, con un indice per nome
+#[derive(Default)]
+struct Columns {
+    list: Vec<Column>,
+    index: HashMap<String, usize>,
+}
+
+impl Columns {
+    /// Indice della colonna, che viene aggiunta se non e' ancora comparsa
+    fn add(
+        &mut self,
+        name: &str,
+        kind: Kind,
+        lower: Option<f64>,
+        upper: Option<f64>,
+    ) -> ParseResult<usize> {
+        if let Some(i) = self.index.get(name) {
+            let column = &self.list[*i];
+            if (column.kind, column.lower, column.upper) != (kind, lower, upper) {
+                return Err(format!(
+                    "Variable {} is declared with different types or bounds",
+                    name
+                ));
+            }
+            return Ok(*i);
+        }
+
+        self.index.insert(name.to_string(), self.list.len());
+        self.list.push(Column {
+            name: name.to_string(),
+            kind,
+            lower,
+            upper,
+        });
+        Ok(self.list.len() - 1)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// Nodo di un'espressione di lp-modeler
+enum Node {
+    Column(usize),
+    Value(f64),
+    Empty,
+    Operation(Operation, usize, usize),
+}
+
+/// Lettore della rappresentazione Debug di un'espressione di lp-modeler
+///
+/// lp-modeler non espone i nodi delle espressioni: la rappresentazione Debug
+/// derivata riporta l'arena dei nodi e la radice, ed e' molto piu' veloce da
+/// produrre del testo LP (che semplifica ogni espressione).
+struct Cursor<'a> {
+    text: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn error<T>(&self) -> ParseResult<T> {
+        let context: String = self.text.chars().take(40).collect();
+        Err(format!("Unexpected lp-modeler expression at: {}", context))
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.text.strip_prefix(prefix) {
+            Some(rest) => {
+                self.text = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, prefix: &str) -> ParseResult<()> {
+        if self.eat(prefix) {
+            Ok(())
+        } else {
+            self.error()
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        let end = self
+            .text
+            .bytes()
+            .position(|b| !(b.is_ascii_alphanumeric() || b"_.-+".contains(&b)))
+            .unwrap_or(self.text.len());
+        let (word, rest) = self.text.split_at(end);
+        self.text = rest;
+        word
+    }
+
+    fn number(&mut self) -> ParseResult<f64> {
+        match self.word().parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => self.error(),
+        }
+    }
+
+    fn index(&mut self) -> ParseResult<usize> {
+        self.word().parse().or_else(|_| self.error())
+    }
+
+    fn string(&mut self) -> ParseResult<&'a str> {
+        self.expect("\"")?;
+        match self.text.find('"') {
+            Some(end) if !self.text[..end].contains('\\') => {
+                let value = &self.text[..end];
+                self.text = &self.text[end + 1..];
+                Ok(value)
+            }
+            _ => self.error(),
+        }
+    }
+
+    fn bound(&mut self) -> ParseResult<Option<f64>> {
+        if self.eat("None") {
+            return Ok(None);
+        }
+        self.expect("Some(")?;
+        let value = self.number()?;
+        self.expect(")")?;
+        Ok(Some(value))
+    }
+
+    /// ConsInt(LpInteger { name: "t0", lower_bound: None, upper_bound: None })
+    fn variable(&mut self, kind: Kind, columns: &mut Columns) -> ParseResult<Node> {
+        self.expect(match kind {
+            Kind::Binary => "(LpBinary { name: ",
+            Kind::Integer => "(LpInteger { name: ",
+            Kind::Continuous => "(LpContinuous { name: ",
+        })?;
+        let name = self.string()?;
+        let (lower, upper) = match kind {
+            Kind::Binary => (None, None),
+            _ => {
+                self.expect(", lower_bound: ")?;
+                let lower = self.bound()?;
+                self.expect(", upper_bound: ")?;
+                (lower, self.bound()?)
+            }
+        };
+        self.expect(" })")?;
+        Ok(Node::Column(columns.add(name, kind, lower, upper)?))
+    }
+
+    fn node(&mut self, columns: &mut Columns) -> ParseResult<Node> {
+        Ok(match self.word() {
+            "ConsBin" => self.variable(Kind::Binary, columns)?,
+            "ConsInt" => self.variable(Kind::Integer, columns)?,
+            "ConsCont" => self.variable(Kind::Continuous, columns)?,
+            "EmptyExpr" => Node::Empty,
+            "LitVal" => {
+                self.expect("(")?;
+                let value = self.number()?;
+                self.expect(")")?;
+                Node::Value(value)
+            }
+            "LpCompExpr" => {
+                self.expect("(")?;
+                let operation = match self.word() {
+                    "Addition" => Operation::Add,
+                    "Subtraction" => Operation::Sub,
+                    "Multiplication" => Operation::Mul,
+                    _ => return self.error(),
+                };
+                self.expect(", ")?;
+                let left = self.index()?;
+                self.expect(", ")?;
+                let right = self.index()?;
+                self.expect(")")?;
+                Node::Operation(operation, left, right)
+            }
+            _ => return self.error(),
+        })
+    }
+}
+
+/// Albero di un'espressione, con le variabili gia' tradotte in colonne
+struct Expression {
+    root: usize,
+    nodes: Vec<Node>,
+}
+
+impl Expression {
+    /// LpExpression { root: 2, arena: [nodo, nodo, ...] }
+    fn new(expr: &LpExpression, columns: &mut Columns) -> ParseResult<Self> {
+        let text = format!("{:?}", expr);
+        let mut cursor = Cursor { text: &text };
+
+        cursor.expect("LpExpression { root: ")?;
+        let root = cursor.index()?;
+        cursor.expect(", arena: [")?;
+
+        let mut nodes = Vec::new();
+        while !cursor.eat("] }") {
+            if !nodes.is_empty() {
+                cursor.expect(", ")?;
+            }
+            nodes.push(cursor.node(columns)?);
+        }
+
+        Ok(Expression { root, nodes })
+    }
+
+    fn node(&self, index: usize) -> ParseResult<&Node> {
+        self.nodes
+            .get(index)
+            .ok_or_else(|| format!("Missing node {} in an lp-modeler expression", index))
+    }
+
+    /// Valore del sottoalbero, se non contiene variabili
+    fn constant(&self, index: usize) -> ParseResult<Option<f64>> {
+        Ok(match self.node(index)? {
+            Node::Column(_) => None,
+            Node::Value(v) => Some(*v),
+            Node::Empty => Some(0.0),
+            Node::Operation(op, l, r) => {
+                let (Some(l), Some(r)) = (self.constant(*l)?, self.constant(*r)?) else {
+                    return Ok(None);
+                };
+                Some(match op {
+                    Operation::Add => l + r,
+                    Operation::Sub => l - r,
+                    Operation::Mul => l * r,
+                })
+            }
+        })
+    }
+
+    /// Coefficienti delle colonne e costante dell'espressione (senza
+    /// ricorsione sulle somme, che possono avere migliaia di termini)
+    fn linear(&self) -> ParseResult<(HashMap<usize, f64>, f64)> {
+        let mut coefs = HashMap::<usize, f64>::new();
+        let mut constant = 0.0;
+
+        let mut stack = vec![(self.root, 1.0)];
+        while let Some((index, factor)) = stack.pop() {
+            match self.node(index)? {
+                Node::Column(c) => *coefs.entry(*c).or_default() += factor,
+                Node::Value(v) => constant += factor * v,
+                Node::Empty => {}
+                Node::Operation(Operation::Add, l, r) => {
+                    stack.push((*r, factor));
+                    stack.push((*l, factor));
+                }
+                Node::Operation(Operation::Sub, l, r) => {
+                    stack.push((*r, -factor));
+                    stack.push((*l, factor));
+                }
+                Node::Operation(Operation::Mul, l, r) => {
+                    match (self.constant(*l)?, self.constant(*r)?) {
+                        (Some(v), _) => stack.push((*r, factor * v)),
+                        (_, Some(v)) => stack.push((*l, factor * v)),
+                        _ => return Err("Nonlinear lp-modeler expression".to_string()),
+                    }
+                }
+            }
+        }
+
+        Ok((coefs, constant))
+    }
+}
+
+/// Vincolo in forma lineare: coefficienti, verso e termine noto
+struct Row {
+    sense: char,
+    coefs: Vec<(usize, f64)>,
+    rhs: f64,
+}
+
+/// Problema lineare ricavato dai vincoli e dalle variabili di un LpProblem
+struct LinearProgram {
+    maximize: bool,
+    objective: Vec<(usize, f64)>,
+    rows: Vec<Row>,
+    columns: Vec<Column>,
+}
+
+/// Termini non nulli ordinati per colonna
+fn sorted_terms(coefs: HashMap<usize, f64>) -> Vec<(usize, f64)> {
+    let mut terms: Vec<(usize, f64)> = coefs.into_iter().filter(|(_, v)| *v != 0.0).collect();
+    terms.sort_by_key(|(c, _)| *c);
+    terms
+}
+
+impl LinearProgram {
+    fn new(problem: &LpProblem) -> ParseResult<Self> {
+        let mut columns = Columns::default();
+
+        let objective = match &problem.obj_expr_arena {
+            Some(expr) => sorted_terms(Expression::new(expr, &mut columns)?.linear()?.0),
+            None => vec![],
+        };
+
+        let mut rows = Vec::with_capacity(problem.constraints.len());
+        for constraint in &problem.constraints {
+            let (mut coefs, lhs_constant) =
+                Expression::new(&constraint.0, &mut columns)?.linear()?;
+            let (rhs_coefs, rhs_constant) =
+                Expression::new(&constraint.2, &mut columns)?.linear()?;
+            for (c, v) in rhs_coefs {
+                *coefs.entry(c).or_default() -= v;
+            }
+            rows.push(Row {
+                sense: match constraint.1 {
+                    Constraint::GreaterOrEqual => 'G',
+                    Constraint::LessOrEqual => 'L',
+                    Constraint::Equal => 'E',
+                },
+                coefs: sorted_terms(coefs),
+                rhs: rhs_constant - lhs_constant,
+            });
+        }
+
+        Ok(LinearProgram {
+            maximize: matches!(problem.objective_type, LpObjective::Maximize),
+            objective,
+            rows,
+            columns: columns.list,
+        })
+    }
+
+    /// Nome della riga, come nel file LP di lp-modeler
+    fn row_name(row: usize) -> String {
+        format!("c{}", row + 1)
+    }
+
+    /// Coefficienti di ogni colonna: (riga, valore), con la riga 0 per la
+    /// funzione obiettivo
+    fn column_entries(&self) -> Vec<Vec<(usize, f64)>> {
+        let mut entries = vec![Vec::<(usize, f64)>::new(); self.columns.len()];
+
+        for (column, value) in &self.objective {
+            entries[*column].push((0, *value));
+        }
+
+        for (r, row) in self.rows.iter().enumerate() {
+            for (column, value) in &row.coefs {
+                entries[*column].push((r + 1, *value));
+            }
+        }
+
+        entries
+    }
+
+    /// Primo nome che non entra nei campi da 8 caratteri del formato fisso
+    fn long_name(&self) -> Option<String> {
+        self.columns
+            .iter()
+            .map(|c| c.name.clone())
+            .chain((0..self.rows.len()).map(Self::row_name))
+            .find(|n| n.len() > 8)
+    }
+
+    fn to_mps(&self, name: &str, free: bool) -> String {
+        let mut buffer = String::new();
+
+        // i write! su una String non possono fallire
+        let line = |buffer: &mut String, a: &str, b: &str, value: f64| {
+            if free {
+                writeln!(buffer, "    {} {} {}", a, b, value).unwrap();
+            } else {
+                writeln!(buffer, "    {:<8}  {:<8}  {:>12}", a, b, value).unwrap();
+            }
+        };
+        let marker = |buffer: &mut String, count: usize, kind: &str| {
+            let name = format!("MARKER{}", count);
+            if free {
+                writeln!(buffer, "    {} 'MARKER' {}", name, kind).unwrap();
+            } else {
+                writeln!(buffer, "    {:<8}  'MARKER'                 {}", name, kind).unwrap();
+            }
+        };
+        let bound = |buffer: &mut String, kind: &str, column: &str, value: Option<f64>| {
+            match (free, value) {
+                (true, Some(v)) => writeln!(buffer, " {} BND {} {}", kind, column, v),
+                (true, None) => writeln!(buffer, " {} BND {}", kind, column),
+                (false, Some(v)) => {
+                    writeln!(buffer, " {} BND       {:<8}  {:>12}", kind, column, v)
+                }
+                (false, None) => writeln!(buffer, " {} BND       {}", kind, column),
+            }
+            .unwrap();
+        };
+
+        let row_names: Vec<String> = std::iter::once("obj".to_string())
+            .chain((0..self.rows.len()).map(Self::row_name))
+            .collect();
+
+        writeln!(buffer, "NAME          {}", name).unwrap();
+
+        if self.maximize {
+            buffer.push_str("OBJSENSE\n    MAX\n");
+        }
+
+        buffer.push_str("ROWS\n N  obj\n");
+        for (r, row) in self.rows.iter().enumerate() {
+            writeln!(buffer, " {}  {}", row.sense, row_names[r + 1]).unwrap();
+        }
+
+        buffer.push_str("COLUMNS\n");
+        let entries = self.column_entries();
+        let mut in_marker = false;
+        let mut marker_count = 0;
+        for (column, column_entries) in self.columns.iter().zip(&entries) {
+            let is_integer = column.kind != Kind::Continuous;
+            if is_integer != in_marker {
+                let kind = if is_integer { "'INTORG'" } else { "'INTEND'" };
+                marker(&mut buffer, marker_count, kind);
+                if !is_integer {
+                    marker_count += 1;
+                }
+                in_marker = is_integer;
+            }
+            for (row, value) in column_entries {
+                line(&mut buffer, &column.name, &row_names[*row], *value);
+            }
+            // keep the column declared even if all its coefficients are 0
+            if column_entries.is_empty() {
+                line(&mut buffer, &column.name, "obj", 0.0);
+            }
+        }
+        if in_marker {
+            marker(&mut buffer, marker_count, "'INTEND'");
+        }
+
+        buffer.push_str("RHS\n");
+        for (r, row) in self.rows.iter().enumerate() {
+            if row.rhs != 0.0 {
+                line(&mut buffer, "RHS", &row_names[r + 1], row.rhs);
+            }
+        }
+
+        buffer.push_str("BOUNDS\n");
+        for column in &self.columns {
+            let name = column.name.as_str();
+            match (column.kind, column.lower, column.upper) {
+                (Kind::Binary, _, _) => bound(&mut buffer, "BV", name, None),
+                // lp-modeler writes continuous variables without bounds as free
+                (Kind::Continuous, None, None) => bound(&mut buffer, "FR", name, None),
+                (kind, lower, upper) => {
+                    if let Some(l) = lower {
+                        bound(&mut buffer, "LO", name, Some(l));
+                    }
+                    match upper {
+                        Some(u) => bound(&mut buffer, "UP", name, Some(u)),
+                        // integer columns inside markers may default to an
+                        // upper bound of 1 on some readers, LP files default
+                        // to +inf
+                        None if kind == Kind::Integer => bound(&mut buffer, "PL", name, None),
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        buffer.push_str("ENDATA\n");
+        buffer
+    }
+}
+
+/// Esportazione di un problema nel formato MPS (fisso o libero)
+pub trait MpsFileFormat {
+    fn to_mps_file_format(&self, free: bool) -> Result<String>;
+    fn write_mps(&self, file_model: &str, free: bool) -> Result<()> {
+        let mps = self.to_mps_file_format(free)?;
+        let mut buffer = File::create(file_model)?;
+        buffer.write_all(mps.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl MpsFileFormat for LpProblem {
+    fn to_mps_file_format(&self, free: bool) -> Result<String> {
+        let program =
+            LinearProgram::new(self).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+        if !free {
+            if let Some(long_name) = program.long_name() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Name {} is longer than 8 characters, which fixed MPS can't hold; use free MPS",
+                        long_name
+                    ),
+                ));
+            }
+        }
+
+        Ok(program.to_mps(self.name, free))
+    }
+}
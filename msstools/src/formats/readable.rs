@@ -0,0 +1,35 @@
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::path::Path;
+
+use crate::pw::Pathway;
+
+/// Scrive il pathway nel formato letto da `parse_readable`: numero e nomi
+/// delle molecole, numero di reazioni e per ognuna nome, reagenti e prodotti
+pub fn write_readable_to<W: Write>(pathway: &Pathway, writer: &mut W) -> Result<()> {
+    let compounds = pathway.get_compounds();
+
+    writeln!(writer, "{}", compounds.len())?;
+    for compound in compounds {
+        writeln!(writer, "{}", compound.name)?;
+    }
+
+    writeln!(writer, "{}", pathway.get_reactions_count())?;
+    for reaction in pathway.get_reactions() {
+        writeln!(writer, "{}", reaction.get_name())?;
+        for side in [reaction.get_substrate(), reaction.get_product()] {
+            writeln!(writer, "{}", side.len())?;
+            for compound in side {
+                writeln!(writer, "{}", compounds[*compound as usize].name)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_readable<P: AsRef<Path>>(pathway: &Pathway, path: P) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_readable_to(pathway, &mut writer)?;
+    writer.flush()
+}
@@ -3,6 +3,10 @@
 //!
 //! La risoluzione vera e propria e' affidata a CPLEX.
 
+pub mod encoders;
+pub mod formats;
 pub mod models;
 pub mod parsers;
 pub mod pw;
+pub mod solutions;
+pub mod solvers;
@@ -1,5 +1,7 @@
 //! Modulo per la generazione dei modelli
 
+pub mod acyclicmodel;
 pub mod bigmmodel;
+pub mod mssmodel;
 pub mod newmodel;
 pub mod timesetmodel;
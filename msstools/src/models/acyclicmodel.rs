@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use log::info;
+use lp_modeler::dsl::{LpBinary, LpExpression, LpOperations, LpProblem};
+
+use crate::models::mssmodel::{Incidence, ModelVariable, MssModel, SeedSolution};
+use crate::pw::Pathway;
+
+/// Modello senza indici temporali: l'autoalimentazione ciclica viene
+/// esclusa con vincoli di eliminazione dei cicli.
+///
+/// Per ogni insieme S di composti almeno uno deve essere un seed o essere
+/// attivato da una reazione che non richiede composti di S:
+///
+/// sum_{i in S} (x_i + sum_{j : R(j) ∩ S = ∅} u_ij) >= 1
+///
+/// Il modello parte dai tagli sulle singole molecole, sui 2-cicli e sulle
+/// componenti fortemente connesse; i tagli violati da una soluzione si
+/// ottengono con `separate` e si aggiungono con `add_cut`. Con i soli
+/// tagli iniziali il modello e' un rilassamento: un ciclo interno ad una
+/// componente piu' grande puo' ancora autoalimentarsi.
+pub struct AcyclicModel {
+    incidence: Incidence,
+    cuts: Vec<Vec<u32>>,
+}
+
+impl AcyclicModel {
+    pub fn new(pathway: &Pathway) -> Self {
+        let incidence = Incidence::new(pathway);
+        let mut cuts = Vec::<Vec<u32>>::new();
+
+        let successors = incidence.compound_successors();
+        for (a, succ) in successors.iter().enumerate() {
+            for b in succ {
+                let b_usize = *b as usize;
+                if a < b_usize && successors[b_usize].contains(&(a as u32)) {
+                    cuts.push(vec![a as u32, *b]);
+                }
+            }
+        }
+
+        for scc in incidence.compound_sccs() {
+            if scc.len() > 2 {
+                cuts.push(scc);
+            }
+        }
+
+        AcyclicModel { incidence, cuts }
+    }
+
+    /// Insiemi di composti per cui e' presente un vincolo di eliminazione
+    pub fn get_cuts(&self) -> &Vec<Vec<u32>> {
+        &self.cuts
+    }
+
+    /// Aggiunge il vincolo di eliminazione per l'insieme di composti
+    pub fn add_cut(&mut self, mut set: Vec<u32>) -> bool {
+        set.sort();
+        set.dedup();
+        if set.is_empty() || self.cuts.contains(&set) {
+            return false;
+        }
+        self.cuts.push(set);
+        true
+    }
+
+    /// Restituisce l'insieme dei composti non raggiunti dal seed set, che
+    /// corrisponde ad un vincolo violato (None se il seed set e' completo)
+    pub fn separate(&self, seeds: &[u32]) -> Option<Vec<u32>> {
+        let cs = self.incidence.get_compounds_count();
+        let mut reached = vec![false; cs];
+        for seed in seeds {
+            reached[*seed as usize] = true;
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (compound, reactions) in self.incidence.comp_produced_by_reac.iter().enumerate() {
+                if reached[compound] {
+                    continue;
+                }
+                let producible = reactions.iter().any(|j| {
+                    self.incidence.reac_requires_comp[*j as usize]
+                        .iter()
+                        .all(|k| reached[*k as usize])
+                });
+                if producible {
+                    reached[compound] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        let unreached: Vec<u32> = (0..cs as u32).filter(|i| !reached[*i as usize]).collect();
+        if unreached.is_empty() {
+            None
+        } else {
+            Some(unreached)
+        }
+    }
+}
+
+impl MssModel for AcyclicModel {
+    fn name(&self) -> &'static str {
+        "Acyclic"
+    }
+
+    fn incidence(&self) -> &Incidence {
+        &self.incidence
+    }
+
+    fn build(&self) -> LpProblem {
+        info!(
+            "Building Acyclic model with {} initial cuts",
+            self.cuts.len()
+        );
+        let cs = self.incidence.get_compounds_count();
+
+        let comp_produced_by_reac = &self.incidence.comp_produced_by_reac;
+        let reac_requires_comp = &self.incidence.reac_requires_comp;
+
+        // i index
+        let mut vars_x = Vec::<LpBinary>::new();
+
+        // ij index
+        let mut vars_u = Vec::<Vec<LpBinary>>::new();
+
+        info!("Generating variables");
+        let mut problem = LpProblem::new("MSS", lp_modeler::dsl::LpObjective::Minimize);
+
+        for (i, reactions) in comp_produced_by_reac.iter().enumerate() {
+            vars_x.push(LpBinary::new(format!("x{}", i).as_str()));
+            vars_u.push(
+                reactions
+                    .iter()
+                    .map(|j| LpBinary::new(format!("u{}_{}", i, j).as_str()))
+                    .collect(),
+            );
+        }
+
+        info!("Generating constraints");
+
+        // target function
+        for var_x in &vars_x {
+            problem += var_x;
+        }
+
+        // x_i + sum (u_ij : i not in R(j)) >= 1
+        for i in 0..cs {
+            let mut expr: LpExpression = (&vars_x[i]).into();
+            for (u, reaction) in vars_u[i].iter().zip(&comp_produced_by_reac[i]) {
+                if !reac_requires_comp[*reaction as usize].contains(&(i as u32)) {
+                    expr += u;
+                }
+            }
+            problem += expr.ge(1);
+        }
+
+        // sum_{i in S} (x_i + sum_{j : R(j) ∩ S = ∅} u_ij) >= 1
+        for cut in &self.cuts {
+            let set: HashSet<u32> = cut.iter().copied().collect();
+            let mut expr = LpExpression::literal(0.0);
+            for i in cut {
+                let i_usize = *i as usize;
+                expr += &vars_x[i_usize];
+                for (u, reaction) in vars_u[i_usize].iter().zip(&comp_produced_by_reac[i_usize]) {
+                    if !reac_requires_comp[*reaction as usize]
+                        .iter()
+                        .any(|k| set.contains(k))
+                    {
+                        expr += u;
+                    }
+                }
+            }
+            problem += expr.ge(1);
+        }
+
+        problem
+    }
+
+    fn variables(&self) -> HashMap<String, ModelVariable> {
+        let mut variables = HashMap::new();
+
+        for (compound, reactions) in self.incidence.comp_produced_by_reac.iter().enumerate() {
+            let i = compound as u32;
+            variables.insert(format!("x{}", i), ModelVariable::Seed(i));
+
+            for j in reactions {
+                variables.insert(format!("u{}_{}", i, j), ModelVariable::Production(i, *j));
+            }
+        }
+
+        variables
+    }
+
+    fn needs_separation(&self) -> bool {
+        true
+    }
+
+    fn add_violated_cuts(&mut self, solution: &SeedSolution) -> Result<usize, String> {
+        let Some(cut) = self.separate(&solution.seeds) else {
+            return Ok(0);
+        };
+
+        // il solver ha restituito una soluzione che viola un vincolo del
+        // modello: ripetere la risoluzione non cambierebbe il risultato
+        if !self.add_cut(cut.clone()) {
+            return Err(format!(
+                "The solution violates the cut {:?}, which is already in the model",
+                cut
+            ));
+        }
+        Ok(1)
+    }
+}
@@ -1,137 +1,166 @@
+use std::collections::HashMap;
+
 use log::info;
 use lp_modeler::dsl::{LpBinary, LpExpression, LpInteger, LpOperations, LpProblem};
 
+use crate::models::mssmodel::{Incidence, ModelVariable, MssModel};
 use crate::pw::Pathway;
 
-/// Genera la prima versione del modello big-M (non documentato nella tesi)
+/// Prima versione del modello big-M (non documentato nella tesi)
 ///
-/// il pathway non deve contenere reazioni con piu' di un prodotto
-pub fn build_bigm_model(pathway: &Pathway, m: i32) -> LpProblem {
-    info!("Building Big-M model with M = {}", m);
-    let rs = pathway.get_reactions_count();
-    let cs = pathway.get_compounds_count();
+/// le reazioni con piu' prodotti sono gestite attivando separatamente
+/// ogni coppia (reazione, prodotto)
+pub struct BigMModel {
+    incidence: Incidence,
+    m: i32,
+}
 
-    let mut comp_produced_by_reac = Vec::<Vec<u32>>::with_capacity(cs);
-    let mut reac_requires_comp = Vec::<Vec<u32>>::with_capacity(rs);
+impl BigMModel {
+    pub fn new(pathway: &Pathway, m: i32) -> Self {
+        BigMModel {
+            incidence: Incidence::new(pathway),
+            m,
+        }
+    }
+}
 
-    for _ in 0..cs {
-        comp_produced_by_reac.push(vec![]);
+impl MssModel for BigMModel {
+    fn name(&self) -> &'static str {
+        "Big-M"
     }
 
-    for _ in 0..rs {
-        reac_requires_comp.push(vec![]);
+    fn incidence(&self) -> &Incidence {
+        &self.incidence
     }
 
-    for reaction in pathway.get_reactions() {
-        for prod in reaction.get_product() {
-            let prod_usize: usize = prod.to_owned() as usize;
-            comp_produced_by_reac[prod_usize].push(reaction.get_id());
-        }
+    fn time_offset(&self) -> u32 {
+        1
+    }
 
-        if reaction.get_product().len() > 1 {
-            panic!("This model doesn't allow multiple-product reactions");
+    fn build(&self) -> LpProblem {
+        let m = self.m;
+        info!("Building Big-M model with M = {}", m);
+        let rs = self.incidence.get_reactions_count();
+        let cs = self.incidence.get_compounds_count();
+
+        let comp_produced_by_reac = &self.incidence.comp_produced_by_reac;
+        let reac_requires_comp = &self.incidence.reac_requires_comp;
+
+        // i index
+        let mut vars_x = Vec::<LpBinary>::new();
+        let mut vars_tm = Vec::<LpInteger>::new();
+
+        // ij index
+        let mut vars_u = Vec::<Vec<LpBinary>>::new();
+
+        // j index
+        let mut vars_tr = Vec::<LpInteger>::new();
+
+        info!("Generating variables");
+        let mut problem = LpProblem::new("MSS", lp_modeler::dsl::LpObjective::Minimize);
+
+        for (i, reactions) in comp_produced_by_reac.iter().enumerate() {
+            vars_x.push(LpBinary::new(format!("x{}", i).as_str()));
+            vars_tm.push(LpInteger::new(format!("tm{}", i).as_str()));
+            vars_u.push(
+                reactions
+                    .iter()
+                    .map(|j| LpBinary::new(format!("u{}_{}", i, j).as_str()))
+                    .collect(),
+            );
         }
 
-        for sub in reaction.get_substrate() {
-            let sub_usize: usize = sub.to_owned() as usize;
-            reac_requires_comp[reaction.get_id() as usize].push(sub_usize as u32);
+        for j in 0..rs {
+            vars_tr.push(LpInteger::new(format!("tr{}", j).as_str()));
         }
-    }
 
-    // i index
-    let mut vars_x = Vec::<LpBinary>::new();
-    let mut vars_tm = Vec::<LpInteger>::new();
+        info!("Generating constraints");
 
-    // j index
-    let mut vars_u = Vec::<LpBinary>::new();
-    let mut vars_tr = Vec::<LpInteger>::new();
-
-    info!("Generating variables");
-    let mut problem = LpProblem::new("MSS", lp_modeler::dsl::LpObjective::Minimize);
+        // target function
+        for var_x in &vars_x {
+            problem += var_x;
+        }
 
-    for i in 0..cs {
-        vars_x.push(LpBinary::new(format!("x{}", i).as_str()));
-        vars_tm.push(LpInteger::new(format!("tm{}", i).as_str()));
-    }
+        info!("0/4");
 
-    for j in 0..rs {
-        let u = LpBinary::new(format!("u{}", j).as_str());
+        // x_i + sum (u_ij) >= 1 for all i
+        for i in 0..cs {
+            let mut expr: LpExpression = (&vars_x[i]).into();
 
-        vars_u.push(u);
-        vars_tr.push(LpInteger::new(format!("tr{}", j).as_str()));
-    }
+            for u in &vars_u[i] {
+                expr += u;
+            }
 
-    info!("Generating constraints");
+            problem += expr.ge(1);
+        }
 
-    // target function
-    for var_x in &vars_x {
-        problem += var_x;
-    }
+        info!("1/4");
 
-    info!("0/4");
+        // tc rkj = 1, pij = 1
 
-    // x_i + sum (pij uj) >= 1 for all i
-    for i in 0..cs {
-        let xi = &vars_x[i];
+        for (compound, reactions) in comp_produced_by_reac.iter().enumerate() {
+            for (u_ij, reaction) in vars_u[compound].iter().zip(reactions) {
+                let trj = &vars_tr[reaction.to_owned() as usize];
 
-        let mut sum_vars = Vec::<&LpBinary>::new();
+                for required in &reac_requires_comp[reaction.to_owned() as usize] {
+                    let tmk = &vars_tm[required.to_owned() as usize];
+                    let xk = &vars_x[required.to_owned() as usize];
 
-        for a in &comp_produced_by_reac[i] {
-            let reac = &vars_u[a.to_owned() as usize];
-            sum_vars.push(reac);
+                    problem += (tmk + 1).le(trj + m - m * u_ij + m * xk);
+                }
+            }
         }
 
-        let mut expr: LpExpression = xi.try_into().unwrap();
+        info!("2/4");
 
-        for sv in sum_vars {
-            expr = expr + sv;
-        }
+        // tc pij = 1
 
-        problem += expr.ge(1);
-    }
+        for (compound, reactions) in comp_produced_by_reac.iter().enumerate() {
+            for (u_ij, reaction) in vars_u[compound].iter().zip(reactions) {
+                let trj = &vars_tr[reaction.to_owned() as usize];
+                let tmi = &vars_tm[compound];
 
-    info!("1/4");
+                problem += (trj).le(tmi + m - m * u_ij);
+            }
+        }
 
-    // tc rij = 1
+        info!("3/4");
 
-    for (reaction, compounds) in reac_requires_comp.iter().enumerate() {
-        for compound in compounds {
-            let tmi = &vars_tm[compound.to_owned() as usize];
-            let trj = &vars_tr[reaction];
-            let uj = &vars_u[reaction];
-            let xi = &vars_x[compound.to_owned() as usize];
+        for tm in &vars_tm {
+            problem += tm.le(m);
+        }
 
-            problem += (tmi + 1).le(trj + m - m * uj + m * xi);
+        for tr in &vars_tr {
+            problem += tr.le(m);
         }
-    }
 
-    info!("2/4");
+        info!("4/4");
 
-    // tc pij = 1
+        problem
+    }
 
-    for (compound, reactions) in comp_produced_by_reac.iter().enumerate() {
-        for reaction in reactions {
-            let trj = &vars_tr[reaction.to_owned() as usize];
-            let tmi = &vars_tm[compound];
-            let uj = &vars_u[reaction.to_owned() as usize];
+    fn variables(&self) -> HashMap<String, ModelVariable> {
+        let mut variables = HashMap::new();
 
-            problem += (trj).le(tmi + m - m * uj);
-        }
-    }
+        for (compound, reactions) in self.incidence.comp_produced_by_reac.iter().enumerate() {
+            let i = compound as u32;
+            variables.insert(format!("x{}", i), ModelVariable::Seed(i));
+            variables.insert(format!("tm{}", i), ModelVariable::CompoundTime(i));
 
-    info!("3/4");
+            for j in reactions {
+                variables.insert(format!("u{}_{}", i, j), ModelVariable::Production(i, *j));
+            }
+        }
 
-    for i in 0..cs {
-        let tm = &vars_tm[i];
-        problem += tm.le(m);
-    }
+        for j in 0..self.incidence.get_reactions_count() as u32 {
+            variables.insert(format!("tr{}", j), ModelVariable::ReactionTime(j));
+        }
 
-    for j in 0..rs {
-        let tr = &vars_tr[j];
-        problem += tr.le(m);
+        variables
     }
+}
 
-    info!("4/4");
-
-    problem
+/// Genera la prima versione del modello big-M (non documentato nella tesi)
+pub fn build_bigm_model(pathway: &Pathway, m: i32) -> LpProblem {
+    BigMModel::new(pathway, m).build()
 }
@@ -0,0 +1,300 @@
+use std::collections::{HashMap, HashSet};
+
+use lp_modeler::dsl::LpProblem;
+use serde::{Deserialize, Serialize};
+
+use crate::pw::Pathway;
+use crate::solutions::is_one;
+
+/// Strutture di incidenza tra composti e reazioni condivise da tutti i
+/// modelli
+pub struct Incidence {
+    /// L'indice i contiene le reazioni che producono il composto i
+    pub comp_produced_by_reac: Vec<Vec<u32>>,
+
+    /// L'indice j contiene i composti richiesti dalla reazione j
+    pub reac_requires_comp: Vec<Vec<u32>>,
+}
+
+impl Incidence {
+    pub fn new(pathway: &Pathway) -> Self {
+        let rs = pathway.get_reactions_count();
+        let cs = pathway.get_compounds_count();
+
+        let mut comp_produced_by_reac = vec![Vec::<u32>::new(); cs];
+        let mut reac_requires_comp = vec![Vec::<u32>::new(); rs];
+
+        for reaction in pathway.get_reactions() {
+            for prod in reaction.get_product() {
+                comp_produced_by_reac[*prod as usize].push(reaction.get_id());
+            }
+
+            for sub in reaction.get_substrate() {
+                reac_requires_comp[reaction.get_id() as usize].push(*sub);
+            }
+        }
+
+        Incidence {
+            comp_produced_by_reac,
+            reac_requires_comp,
+        }
+    }
+
+    pub fn get_compounds_count(&self) -> usize {
+        self.comp_produced_by_reac.len()
+    }
+
+    pub fn get_reactions_count(&self) -> usize {
+        self.reac_requires_comp.len()
+    }
+
+    /// Grafo delle dipendenze tra composti: l'indice k contiene i composti
+    /// prodotti da una reazione che richiede k
+    pub fn compound_successors(&self) -> Vec<Vec<u32>> {
+        let mut successors = vec![Vec::<u32>::new(); self.get_compounds_count()];
+
+        for (compound, reactions) in self.comp_produced_by_reac.iter().enumerate() {
+            for reaction in reactions {
+                for required in &self.reac_requires_comp[*reaction as usize] {
+                    successors[*required as usize].push(compound as u32);
+                }
+            }
+        }
+
+        for succ in &mut successors {
+            succ.sort();
+            succ.dedup();
+        }
+
+        successors
+    }
+
+    /// Componenti fortemente connesse del grafo delle dipendenze tra composti
+    /// (algoritmo di Tarjan in versione iterativa)
+    pub fn compound_sccs(&self) -> Vec<Vec<u32>> {
+        let successors = self.compound_successors();
+        let cs = successors.len();
+
+        let mut index = vec![usize::MAX; cs];
+        let mut lowlink = vec![0usize; cs];
+        let mut on_stack = vec![false; cs];
+        let mut stack = Vec::<usize>::new();
+        let mut sccs = Vec::<Vec<u32>>::new();
+        let mut counter = 0;
+
+        for root in 0..cs {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            // (node, next successor to visit)
+            let mut calls = vec![(root, 0usize)];
+            index[root] = counter;
+            lowlink[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, next)) = calls.pop() {
+                if next < successors[node].len() {
+                    calls.push((node, next + 1));
+                    let succ = successors[node][next] as usize;
+                    if index[succ] == usize::MAX {
+                        index[succ] = counter;
+                        lowlink[succ] = counter;
+                        counter += 1;
+                        stack.push(succ);
+                        on_stack[succ] = true;
+                        calls.push((succ, 0));
+                    } else if on_stack[succ] {
+                        lowlink[node] = lowlink[node].min(index[succ]);
+                    }
+                    continue;
+                }
+
+                if let Some((parent, _)) = calls.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                }
+
+                if lowlink[node] == index[node] {
+                    let mut scc = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        scc.push(member as u32);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    scc.sort();
+                    sccs.push(scc);
+                }
+            }
+        }
+
+        sccs
+    }
+}
+
+/// Significato di una variabile del modello rispetto al pathway
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelVariable {
+    /// Il composto fa parte del seed set
+    Seed(u32),
+
+    /// Istante di attivazione del composto
+    CompoundTime(u32),
+
+    /// La reazione e' attiva
+    Reaction(u32),
+
+    /// Istante di attivazione della reazione
+    ReactionTime(u32),
+
+    /// La reazione (secondo campo) attiva il composto (primo campo)
+    Production(u32, u32),
+
+    /// Il composto e' disponibile all'istante indicato
+    CompoundAt(u32, u32),
+
+    /// La reazione e' attiva all'istante indicato
+    ReactionAt(u32, u32),
+}
+
+/// Seed set e istanti di attivazione ricavati dalla soluzione del solver
+#[derive(Debug)]
+pub struct SeedSolution {
+    /// Composti del seed set
+    pub seeds: Vec<u32>,
+
+    /// Istante di attivazione dichiarato per ogni composto (se presente)
+    pub activation: Vec<Option<u32>>,
+}
+
+/// Interfaccia comune alle formulazioni del problema
+pub trait MssModel {
+    /// Nome del modello usato nei log
+    fn name(&self) -> &'static str;
+
+    /// Strutture di incidenza usate per costruire il modello
+    fn incidence(&self) -> &Incidence;
+
+    /// Ritardo tra i passi della chiusura in avanti e gli istanti del
+    /// modello: i modelli big-M non impongono un passo ai prodotti di
+    /// reazioni che richiedono solo seed
+    fn time_offset(&self) -> u32 {
+        0
+    }
+
+    /// Costruisce il problema da passare al solver
+    fn build(&self) -> LpProblem;
+
+    /// Associa ad ogni nome di variabile il suo significato nel pathway
+    fn variables(&self) -> HashMap<String, ModelVariable>;
+
+    /// Ricava seed set e istanti di attivazione dai valori delle variabili.
+    /// Le variabili binarie sono attive se distano al piu' `tolerance` da 1;
+    /// i solver che scrivono solo i valori non nulli omettono gli istanti
+    /// t = 0, quindi un composto con variabile di tempo assente e' attivo
+    /// all'istante 0.
+    fn decode(&self, values: &HashMap<String, f64>, tolerance: f64) -> SeedSolution {
+        let cs = self.incidence().get_compounds_count();
+        let variables = self.variables();
+
+        let mut seeds = vec![];
+        let mut activation: Vec<Option<u32>> = vec![None; cs];
+        for variable in variables.values() {
+            if let ModelVariable::CompoundTime(i) = variable {
+                activation[*i as usize] = Some(0);
+            }
+        }
+
+        for (name, value) in values {
+            match variables.get(name) {
+                Some(ModelVariable::Seed(i)) if is_one(*value, tolerance) => {
+                    seeds.push(*i);
+                }
+                Some(ModelVariable::CompoundTime(i)) => {
+                    activation[*i as usize] = Some(value.round().max(0.0) as u32);
+                }
+                Some(ModelVariable::CompoundAt(i, t)) if is_one(*value, tolerance) => {
+                    let current = &mut activation[*i as usize];
+                    if current.is_none_or(|c| *t < c) {
+                        *current = Some(*t);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        seeds.sort();
+        for seed in &seeds {
+            activation[*seed as usize] = Some(0);
+        }
+
+        SeedSolution { seeds, activation }
+    }
+
+    /// Vero se il modello contiene solo una parte dei vincoli e va risolto
+    /// aggiungendo i tagli violati (il file esportato da solo e' un
+    /// rilassamento)
+    fn needs_separation(&self) -> bool {
+        false
+    }
+
+    /// Aggiunge i vincoli violati dalla soluzione (per le formulazioni
+    /// risolte a tagli) e restituisce quanti ne sono stati aggiunti.
+    /// Restituisce un errore se un vincolo violato e' gia' presente nel
+    /// modello, cioe' se la soluzione del solver non rispetta il modello.
+    fn add_violated_cuts(&mut self, _solution: &SeedSolution) -> Result<usize, String> {
+        Ok(0)
+    }
+}
+
+/// Composto con un istante di attivazione dichiarato dal solver precedente a
+/// quello in cui viene effettivamente raggiunto
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TimeViolation {
+    pub compound: u32,
+
+    /// Istante dichiarato, gia' corretto con `time_offset`
+    pub claimed: u32,
+
+    /// Primo passo della chiusura in cui il composto e' disponibile
+    /// (None se non viene mai raggiunto)
+    pub earliest: Option<u32>,
+}
+
+/// Confronta gli istanti decodificati dalla soluzione con la chiusura in
+/// avanti del seed set
+pub fn time_violations(
+    model: &dyn MssModel,
+    pathway: &Pathway,
+    solution: &SeedSolution,
+) -> Vec<TimeViolation> {
+    let seeds: HashSet<u32> = solution.seeds.iter().copied().collect();
+    let closure = pathway.closure(&seeds);
+    let offset = model.time_offset();
+
+    solution
+        .activation
+        .iter()
+        .enumerate()
+        .filter_map(|(i, claimed)| {
+            let compound = i as u32;
+            let claimed = if seeds.contains(&compound) {
+                0
+            } else {
+                claimed.as_ref()? + offset
+            };
+            let earliest = closure.get_activation(compound);
+            match earliest {
+                Some(earliest) if claimed >= earliest => None,
+                _ => Some(TimeViolation {
+                    compound,
+                    claimed,
+                    earliest,
+                }),
+            }
+        })
+        .collect()
+}
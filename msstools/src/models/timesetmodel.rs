@@ -1,130 +1,156 @@
+use std::collections::HashMap;
+
+use crate::models::mssmodel::{Incidence, ModelVariable, MssModel};
 use crate::pw::Pathway;
 use log::info;
 use lp_modeler::dsl::{LpBinary, LpExpression, LpOperations, LpProblem};
 
-/// Genera il modello basato sul timeset
-pub fn build_timeset_model(pathway: &Pathway, maxt: usize) -> LpProblem {
-    info!("Building TimeSet model with T = {}", maxt);
-    let rs = pathway.get_reactions_count();
-    let cs = pathway.get_compounds_count();
+/// Modello basato sul timeset
+pub struct TimeSetModel {
+    incidence: Incidence,
+    maxt: usize,
+}
 
-    let mut comp_produced_by_reac = Vec::<Vec<u32>>::with_capacity(cs);
-    let mut reac_requires_comp = Vec::<Vec<u32>>::with_capacity(rs);
+impl TimeSetModel {
+    pub fn new(pathway: &Pathway, maxt: usize) -> Self {
+        TimeSetModel {
+            incidence: Incidence::new(pathway),
+            maxt,
+        }
+    }
+}
 
-    for _ in 0..cs {
-        comp_produced_by_reac.push(vec![]);
+impl MssModel for TimeSetModel {
+    fn name(&self) -> &'static str {
+        "TimeSet"
     }
 
-    for _ in 0..rs {
-        reac_requires_comp.push(vec![]);
+    fn incidence(&self) -> &Incidence {
+        &self.incidence
     }
 
-    for reaction in pathway.get_reactions() {
-        for prod in reaction.get_product() {
-            let prod_usize: usize = prod.to_owned() as usize;
-            comp_produced_by_reac[prod_usize].push(reaction.get_id());
+    fn build(&self) -> LpProblem {
+        let maxt = self.maxt;
+        info!("Building TimeSet model with T = {}", maxt);
+        let rs = self.incidence.get_reactions_count();
+        let cs = self.incidence.get_compounds_count();
+
+        let comp_produced_by_reac = &self.incidence.comp_produced_by_reac;
+        let reac_requires_comp = &self.incidence.reac_requires_comp;
+
+        let mut vars_x = Vec::<LpBinary>::new();
+        let mut vars_d = Vec::<Vec<LpBinary>>::new();
+        let mut vars_s = Vec::<Vec<LpBinary>>::new();
+
+        info!("Generating variables");
+        // Create vars_x and vars_d
+        for i in 0..cs {
+            vars_x.push(LpBinary::new(format!("x{}", i).as_str()));
+
+            let entry: Vec<LpBinary> = (0..maxt)
+                .map(|x| LpBinary::new(format!("d{}_{}", i, x).as_str()))
+                .collect();
+
+            vars_d.push(entry);
         }
 
-        for sub in reaction.get_substrate() {
-            let sub_usize: usize = sub.to_owned() as usize;
-            reac_requires_comp[reaction.get_id() as usize].push(sub_usize as u32);
+        // Create vars_s
+        for j in 0..rs {
+            let entry: Vec<LpBinary> = (0..maxt)
+                .map(|x| LpBinary::new(format!("s{}_{}", j, x).as_str()))
+                .collect();
+
+            vars_s.push(entry);
         }
-    }
 
-    let mut vars_x = Vec::<LpBinary>::new();
-    let mut vars_d = Vec::<Vec<LpBinary>>::new();
-    let mut vars_s = Vec::<Vec<LpBinary>>::new();
+        let mut problem = LpProblem::new("MSS", lp_modeler::dsl::LpObjective::Minimize);
 
-    info!("Generating variables");
-    // Create vars_x and vars_d
-    for i in 0..cs {
-        vars_x.push(LpBinary::new(format!("x{}", i).as_str()));
+        info!("Generating constraints");
 
-        let entry: Vec<LpBinary> = (0..maxt)
-            .into_iter()
-            .map(|x| LpBinary::new(format!("d{}_{}", i, x).as_str()))
-            .collect::<Vec<LpBinary>>()
-            .try_into()
-            .unwrap();
+        // target function
+        for var_x in &vars_x {
+            problem += var_x;
+        }
 
-        vars_d.push(entry);
-    }
+        info!("0/4");
 
-    // Create vars_s
-    for j in 0..rs {
-        let entry: Vec<LpBinary> = (0..maxt)
-            .into_iter()
-            .map(|x| LpBinary::new(format!("s{}_{}", j, x).as_str()))
-            .collect::<Vec<LpBinary>>()
-            .try_into()
-            .unwrap();
+        // d_i0 = x_i
+        for i in 0..cs {
+            let left = &vars_d[i][0];
+            let right = &vars_x[i];
+            problem += left.equal(right);
+        }
 
-        vars_s.push(entry);
-    }
+        info!("1/4");
 
-    let mut problem = LpProblem::new("MSS", lp_modeler::dsl::LpObjective::Minimize);
+        // d_i(T-1) = 1
+        for d in &vars_d {
+            let left = &d[maxt - 1];
+            problem += left.equal(1);
+        }
 
-    info!("Generating constraints");
+        info!("2/4");
 
-    // target function
-    for var_x in &vars_x {
-        problem += var_x;
-    }
+        // d_it >= s_jt
+        for (reaction, compounds) in reac_requires_comp.iter().enumerate() {
+            for compound in compounds {
+                for t in 0..maxt {
+                    let left = &vars_d[compound.to_owned() as usize][t];
+                    let right = &vars_s[reaction][t];
+                    problem += left.ge(right);
+                }
+            }
+        }
 
-    info!("0/4");
+        info!("3/4");
 
-    // d_i0 = x_i
-    for i in 0..cs {
-        let left = &vars_d[i][0];
-        let right = &vars_x[i];
-        problem += left.equal(right);
-    }
+        for i in 0..cs {
+            for t in 1..maxt {
+                let left = &vars_d[i][t];
+                let right = &vars_d[i][t - 1];
 
-    info!("1/4");
+                let mut right_vars = Vec::<&LpBinary>::new();
 
-    // d_i(T-1) = 1
-    for i in 0..cs {
-        let left = &vars_d[i][maxt - 1];
-        problem += left.equal(1);
-    }
+                for reaction in &comp_produced_by_reac[i] {
+                    let other_right = &vars_s[reaction.to_owned() as usize][t - 1];
+                    right_vars.push(other_right);
+                }
 
-    info!("2/4");
+                let mut right_expr: LpExpression = right.into();
+                for rv in right_vars {
+                    right_expr += rv;
+                }
 
-    // d_it >= s_jt
-    for (reaction, compounds) in reac_requires_comp.iter().enumerate() {
-        for compound in compounds {
-            for t in 0..maxt {
-                let left = &vars_d[compound.to_owned() as usize][t];
-                let right = &vars_s[reaction][t];
-                problem += left.ge(right);
+                problem += left.le(right_expr);
             }
         }
-    }
 
-    info!("3/4");
+        info!("4/4");
 
-    for i in 0..cs {
-        for t in 1..maxt {
-            let left = &vars_d[i][t];
-            let right = &vars_d[i][t - 1];
+        problem
+    }
 
-            let mut right_vars = Vec::<&LpBinary>::new();
+    fn variables(&self) -> HashMap<String, ModelVariable> {
+        let mut variables = HashMap::new();
 
-            for reaction in &comp_produced_by_reac[i] {
-                let other_right = &vars_s[reaction.to_owned() as usize][t - 1];
-                right_vars.push(other_right);
+        for i in 0..self.incidence.get_compounds_count() as u32 {
+            variables.insert(format!("x{}", i), ModelVariable::Seed(i));
+            for t in 0..self.maxt as u32 {
+                variables.insert(format!("d{}_{}", i, t), ModelVariable::CompoundAt(i, t));
             }
+        }
 
-            let mut right_expr: LpExpression = right.try_into().unwrap();
-            for rv in right_vars {
-                right_expr = right_expr + rv;
+        for j in 0..self.incidence.get_reactions_count() as u32 {
+            for t in 0..self.maxt as u32 {
+                variables.insert(format!("s{}_{}", j, t), ModelVariable::ReactionAt(j, t));
             }
-
-            problem += left.le(right_expr);
         }
-    }
 
-    info!("4/4");
+        variables
+    }
+}
 
-    problem
+/// Genera il modello basato sul timeset
+pub fn build_timeset_model(pathway: &Pathway, maxt: usize) -> LpProblem {
+    TimeSetModel::new(pathway, maxt).build()
 }
@@ -1,4 +1,5 @@
 //! Modulo per il parsing di un pathway metabolico da un file
 
+pub mod parsejson;
 pub mod parsepddl;
 pub mod parsereadable;
@@ -0,0 +1,11 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use crate::pw::Pathway;
+
+/// Legge un pathway esportato in json dal generatore
+pub fn parse_json(input: PathBuf) -> Pathway {
+    let file = File::open(input).expect("Can't open file");
+    let reader = BufReader::new(file);
+
+    serde_json::from_reader(reader).expect("Error parsing json")
+}
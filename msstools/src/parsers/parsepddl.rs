@@ -17,7 +17,7 @@ pub fn parse_pddl(input: PathBuf) -> Pathway {
     file.read_to_string(&mut buffer).expect("Can't read file");
 
     let binding = buffer.replace("\r", "").replace("\t", "").replace(" ", "");
-    let mut entries = binding.split("\n");
+    let entries = binding.split('\n');
 
     let mut reaction_counter = 0;
     let mut compound_counter = 0;
@@ -31,7 +31,7 @@ pub fn parse_pddl(input: PathBuf) -> Pathway {
     let mut reading_substrate = true;
     let mut reading_reaction = false;
 
-    while let Some(line) = entries.next() {
+    for line in entries {
         trace!("Read: {}", line);
         if line.starts_with("(:actionreaction_") {
             trace!("Found new reaction");
@@ -41,12 +41,12 @@ pub fn parse_pddl(input: PathBuf) -> Pathway {
                 trace!("\tSubstrate:");
                 for sub in &current_substrate {
                     trace!("\t\t{}", sub);
-                    reac.add_substrate(sub.clone());
+                    reac.add_substrate(*sub);
                 }
                 trace!("\tProduct:");
                 for prod in &current_product {
                     trace!("\t\t{}", prod);
-                    reac.add_product(prod.clone());
+                    reac.add_product(*prod);
                 }
 
                 pathway.add_reaction(reac);
@@ -89,11 +89,11 @@ pub fn parse_pddl(input: PathBuf) -> Pathway {
     trace!("Saving last reaction: {}", current_name);
     let mut reac = Reaction::new(reaction_counter, current_name.clone());
     for sub in &current_substrate {
-        reac.add_substrate(sub.clone());
+        reac.add_substrate(*sub);
     }
 
     for prod in &current_product {
-        reac.add_product(prod.clone());
+        reac.add_product(*prod);
     }
 
     pathway.add_reaction(reac);
@@ -24,7 +24,7 @@ pub fn parse_readable(input: PathBuf) -> Pathway {
 
     let buffer_reader = BufReader::new(file);
 
-    return parse_readable_internal(buffer_reader);
+    parse_readable_internal(buffer_reader)
 }
 
 fn parse_readable_internal(reader: BufReader<File>) -> Pathway {
@@ -43,14 +43,11 @@ fn parse_readable_internal(reader: BufReader<File>) -> Pathway {
 
     let comps_it = it.clone().take(compounds_count);
 
-    let mut compound_id = 0u32;
-    for comp in comps_it {
-        let c = Compound::new(compound_id, comp.clone());
+    for (compound_id, comp) in comps_it.enumerate() {
+        let c = Compound::new(compound_id as u32, comp.clone());
 
         trace!("Added: {:?}", c);
         pathway.add_compound(c);
-
-        compound_id += 1;
     }
 
     let mut reacs_it = it.clone().skip(compounds_count);
@@ -83,5 +80,5 @@ fn parse_readable_internal(reader: BufReader<File>) -> Pathway {
         trace!("Added: {:?}", reaction);
         pathway.add_reaction(reaction);
     }
-    return pathway;
+    pathway
 }
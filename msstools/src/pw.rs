@@ -1,9 +1,26 @@
 //! Modulo che gestisce la rappresentazione di un pathway
 
+mod closure;
+mod community;
 mod compound;
+mod contraction;
+mod diff;
+mod explain;
+mod minimality;
 mod pathway;
 mod reaction;
+mod stats;
+mod subpathway;
 
+pub use closure::Closure;
+pub use community::{merge_community, CommunityOptions};
 pub use compound::Compound;
+pub use contraction::{ContractedCompound, ContractionMap};
+pub use diff::{
+    diff_pathways, diff_seed_sets, DiffReaction, PathwayDiff, ReactionChange, SeedSetDiff,
+};
+pub use explain::{explain_unreached, BlockedCompound, BlockedReaction, Explanation};
+pub use minimality::{check_minimality, MinimalityReport, SeedSwap};
 pub use pathway::Pathway;
 pub use reaction::Reaction;
+pub use stats::{Distribution, PathwayStats};
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use crate::pw::Pathway;
+
+/// Chiusura in avanti di un insieme di molecole: ad ogni passo vengono
+/// applicate contemporaneamente tutte le reazioni i cui reagenti sono
+/// gia' disponibili
+#[derive(Debug, Clone)]
+pub struct Closure {
+    /// Passo in cui ogni molecola viene raggiunta (0 per i seed)
+    activation: Vec<Option<u32>>,
+
+    /// Reazione che ha prodotto ogni molecola (None per i seed)
+    producer: Vec<Option<u32>>,
+
+    /// Numero di passi che hanno aggiunto almeno una molecola
+    steps: u32,
+}
+
+impl Closure {
+    pub fn new(pathway: &Pathway, seeds: &HashSet<u32>) -> Self {
+        let cs = pathway.get_compounds_count();
+        let mut activation = vec![None; cs];
+        let mut producer = vec![None; cs];
+
+        for seed in seeds {
+            activation[*seed as usize] = Some(0);
+        }
+
+        let mut steps = 0;
+        loop {
+            let mut to_add = vec![];
+            for reac in pathway.get_reactions() {
+                let active = reac
+                    .get_substrate()
+                    .iter()
+                    .all(|s| activation[*s as usize].is_some_and(|t| t <= steps));
+                if !active {
+                    continue;
+                }
+                for p in reac.get_product() {
+                    if activation[*p as usize].is_none() {
+                        to_add.push((*p, reac.get_id()));
+                    }
+                }
+            }
+
+            if to_add.is_empty() {
+                break;
+            }
+
+            steps += 1;
+            for (p, r) in to_add {
+                if activation[p as usize].is_none() {
+                    activation[p as usize] = Some(steps);
+                    producer[p as usize] = Some(r);
+                }
+            }
+        }
+
+        Closure {
+            activation,
+            producer,
+            steps,
+        }
+    }
+
+    pub fn get_steps(&self) -> u32 {
+        self.steps
+    }
+
+    pub fn get_activation(&self, compound: u32) -> Option<u32> {
+        self.activation[compound as usize]
+    }
+
+    pub fn get_producer(&self, compound: u32) -> Option<u32> {
+        self.producer[compound as usize]
+    }
+
+    pub fn is_reached(&self, compound: u32) -> bool {
+        self.activation[compound as usize].is_some()
+    }
+
+    pub fn get_reached_count(&self) -> usize {
+        self.activation.iter().filter(|a| a.is_some()).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.activation.iter().all(|a| a.is_some())
+    }
+
+    /// Molecole non raggiunte
+    pub fn get_unreached(&self) -> Vec<u32> {
+        (0..self.activation.len() as u32)
+            .filter(|i| !self.is_reached(*i))
+            .collect()
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::pw::{Compound, Pathway, Reaction};
+
+/// Opzioni per l'unione dei pathway di una comunita'
+#[derive(Debug, Clone, Default)]
+pub struct CommunityOptions {
+    /// Le molecole di ogni organismo diventano "<organismo>:<nome>"; solo
+    /// quelle di scambio sono collegate all'ambiente comune
+    pub compartments: bool,
+
+    /// Molecole di scambio (solo con i compartimenti). Se None sono quelle
+    /// presenti in almeno due organismi
+    pub exchange: Option<HashSet<String>>,
+}
+
+/// Pathway della comunita' in costruzione, con le molecole unificate per nome
+struct CommunityBuilder {
+    pathway: Pathway,
+    ids: HashMap<String, u32>,
+}
+
+impl CommunityBuilder {
+    fn compound(&mut self, name: String) -> u32 {
+        if let Some(id) = self.ids.get(&name) {
+            return *id;
+        }
+        let id = self.pathway.get_compounds_count() as u32;
+        self.ids.insert(name.clone(), id);
+        self.pathway.add_compound(Compound::new(id, name));
+        id
+    }
+
+    fn reaction(&mut self, name: String, substrate: &[u32], product: &[u32]) {
+        let mut reaction = Reaction::new(self.pathway.get_reactions_count() as u32, name);
+        substrate.iter().for_each(|s| reaction.add_substrate(*s));
+        product.iter().for_each(|p| reaction.add_product(*p));
+        self.pathway.add_reaction(reaction);
+    }
+}
+
+/// Unisce i pathway degli organismi (nome, pathway) in un'unica rete. Le
+/// reazioni diventano "<organismo>:<nome>"; senza compartimenti le molecole
+/// sono condivise per nome, con i compartimenti ogni molecola di scambio c
+/// ha una copia nell'ambiente e le reazioni "<organismo>:export:c" e
+/// "<organismo>:import:c".
+pub fn merge_community(members: &[(String, &Pathway)], options: &CommunityOptions) -> Pathway {
+    let exchange = options.exchange.clone().unwrap_or_else(|| {
+        let mut occurrences = HashMap::<&str, usize>::new();
+        for (_, pathway) in members {
+            let names: HashSet<&str> = pathway
+                .get_compounds()
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect();
+            for name in names {
+                *occurrences.entry(name).or_default() += 1;
+            }
+        }
+        occurrences
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    });
+
+    let mut community = CommunityBuilder {
+        pathway: Pathway::new(),
+        ids: HashMap::new(),
+    };
+
+    for (organism, pathway) in members {
+        let local: Vec<u32> = pathway
+            .get_compounds()
+            .iter()
+            .map(|c| {
+                if options.compartments {
+                    community.compound(format!("{}:{}", organism, c.name))
+                } else {
+                    community.compound(c.name.clone())
+                }
+            })
+            .collect();
+
+        for reaction in pathway.get_reactions() {
+            let map =
+                |side: &Vec<u32>| -> Vec<u32> { side.iter().map(|c| local[*c as usize]).collect() };
+            community.reaction(
+                format!("{}:{}", organism, reaction.get_name()),
+                &map(reaction.get_substrate()),
+                &map(reaction.get_product()),
+            );
+        }
+
+        if !options.compartments {
+            continue;
+        }
+
+        let mut exchanged: Vec<&Compound> = pathway
+            .get_compounds()
+            .iter()
+            .filter(|c| exchange.contains(&c.name))
+            .collect();
+        exchanged.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for compound in exchanged {
+            let internal = local[compound.id as usize];
+            let environment = community.compound(compound.name.clone());
+            community.reaction(
+                format!("{}:export:{}", organism, compound.name),
+                &[internal],
+                &[environment],
+            );
+            community.reaction(
+                format!("{}:import:{}", organism, compound.name),
+                &[environment],
+                &[internal],
+            );
+        }
+    }
+
+    community.pathway
+}
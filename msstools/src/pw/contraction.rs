@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, HashSet};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::models::mssmodel::Incidence;
+use crate::pw::{Compound, Pathway, Reaction};
+
+/// Molecola del pathway contratto: ha il nome del rappresentante della
+/// classe, che da solo raggiunge tutti gli altri membri
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractedCompound {
+    pub name: String,
+
+    /// Nomi originali delle molecole della classe
+    pub members: Vec<String>,
+}
+
+/// Corrispondenza tra le molecole del pathway contratto (per id) e quelle
+/// originali
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractionMap {
+    pub compounds: Vec<ContractedCompound>,
+}
+
+impl ContractionMap {
+    /// Nessuna contrazione: ogni molecola e' una classe
+    pub fn identity(pathway: &Pathway) -> Self {
+        ContractionMap {
+            compounds: pathway
+                .get_compounds()
+                .iter()
+                .map(|c| ContractedCompound {
+                    name: c.name.clone(),
+                    members: vec![c.name.clone()],
+                })
+                .collect(),
+        }
+    }
+
+    /// Contrazione ottenuta applicando prima questa e poi `next`
+    pub fn then(&self, next: &ContractionMap) -> Self {
+        let by_name: BTreeMap<&str, &ContractedCompound> = self
+            .compounds
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+
+        ContractionMap {
+            compounds: next
+                .compounds
+                .iter()
+                .map(|c| {
+                    let mut members: Vec<String> = c
+                        .members
+                        .iter()
+                        .flat_map(|m| by_name[m.as_str()].members.iter().cloned())
+                        .collect();
+                    members.sort();
+                    ContractedCompound {
+                        name: c.name.clone(),
+                        members,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Molecole originali rimosse dalla contrazione
+    pub fn contracted_count(&self) -> usize {
+        self.compounds.iter().map(|c| c.members.len() - 1).sum()
+    }
+
+    pub fn members(&self, compound: u32) -> &[String] {
+        &self.compounds[compound as usize].members
+    }
+
+    /// Seed set del pathway originale corrispondente a quello del pathway
+    /// contratto: il rappresentante di ogni classe, che ha lo stesso nome
+    pub fn expand_seeds(&self, seeds: &HashSet<u32>) -> Vec<String> {
+        let mut names: Vec<String> = seeds
+            .iter()
+            .map(|s| self.compounds[*s as usize].name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// Union-find in cui la radice di ogni classe e' il suo rappresentante
+struct Classes {
+    parent: Vec<usize>,
+}
+
+impl Classes {
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Unisce la classe di `child` a quella di `root`, che resta il
+    /// rappresentante
+    fn merge(&mut self, child: usize, root: usize) -> bool {
+        let (child, root) = (self.find(child), self.find(root));
+        if child == root {
+            return false;
+        }
+        self.parent[child] = root;
+        true
+    }
+}
+
+impl Pathway {
+    /// Contrae le molecole equivalenti ai fini del seed set minimo:
+    /// - le molecole che si raggiungono a vicenda con reazioni ad un solo
+    ///   reagente (componenti fortemente connesse);
+    /// - le classi con un'unica reazione produttrice esterna, i cui reagenti
+    ///   sono tutti in un'altra classe (catene 1->1), che vengono unite a
+    ///   quella classe.
+    ///
+    /// Ogni classe ha un rappresentante che raggiunge tutti i membri, quindi
+    /// la dimensione del seed set minimo non cambia.
+    /// WARNING: This changes the IDs of the compounds and of the reactions!
+    pub fn contract_equivalent(&mut self) -> ContractionMap {
+        let cs = self.get_compounds_count();
+        let mut classes = Classes {
+            parent: (0..cs).collect(),
+        };
+
+        // grafo delle reazioni con un solo reagente
+        let incidence = Incidence::new(self);
+        let single = Incidence {
+            comp_produced_by_reac: incidence
+                .comp_produced_by_reac
+                .iter()
+                .map(|producers| {
+                    producers
+                        .iter()
+                        .copied()
+                        .filter(|r| incidence.reac_requires_comp[*r as usize].len() == 1)
+                        .collect()
+                })
+                .collect(),
+            reac_requires_comp: incidence.reac_requires_comp.clone(),
+        };
+        for scc in single.compound_sccs() {
+            for member in &scc[1..] {
+                classes.merge(*member as usize, scc[0] as usize);
+            }
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            // reazioni che producono una classe senza consumarla
+            let mut external = BTreeMap::<usize, HashSet<u32>>::new();
+            for reaction in self.get_reactions() {
+                let substrate: HashSet<usize> = reaction
+                    .substrate
+                    .iter()
+                    .map(|s| classes.find(*s as usize))
+                    .collect();
+                for prod in &reaction.product {
+                    let class = classes.find(*prod as usize);
+                    if !substrate.contains(&class) {
+                        external.entry(class).or_default().insert(reaction.id);
+                    }
+                }
+            }
+
+            for (class, producers) in external {
+                if producers.len() != 1 {
+                    continue;
+                }
+                let reaction = &self.get_reactions()[*producers.iter().next().unwrap() as usize];
+                let substrate: HashSet<usize> = reaction
+                    .substrate
+                    .iter()
+                    .map(|s| classes.find(*s as usize))
+                    .collect();
+                if substrate.len() != 1 {
+                    continue;
+                }
+                let root = *substrate.iter().next().unwrap();
+                if classes.merge(class, root) {
+                    debug!("Contracting {} into {}", class, root);
+                    changed = true;
+                }
+            }
+        }
+
+        // nuove molecole nell'ordine dei rappresentanti
+        let roots: Vec<usize> = (0..cs).map(|c| classes.find(c)).collect();
+        let mut new_id = vec![0u32; cs];
+        let mut map = ContractionMap::default();
+        let mut pathway = Pathway::new();
+        for (c, root) in roots.iter().enumerate() {
+            if c == *root {
+                let name = self.get_compounds()[c].name.clone();
+                new_id[c] = pathway.get_compounds_count() as u32;
+                pathway.add_compound(Compound::new(new_id[c], name.clone()));
+                map.compounds.push(ContractedCompound {
+                    name,
+                    members: vec![],
+                });
+            }
+        }
+        for (c, root) in roots.iter().enumerate() {
+            new_id[c] = new_id[*root];
+            map.compounds[new_id[c] as usize]
+                .members
+                .push(self.get_compounds()[c].name.clone());
+        }
+        for class in &mut map.compounds {
+            class.members.sort();
+        }
+
+        // le reazioni perdono i prodotti gia' tra i reagenti e quelle senza
+        // prodotti vengono eliminate
+        for reaction in self.get_reactions() {
+            let mut new_reaction = Reaction::new(
+                pathway.get_reactions_count() as u32,
+                reaction.get_name().clone(),
+            );
+            for sub in &reaction.substrate {
+                let sub = new_id[*sub as usize];
+                if !new_reaction.substrate.contains(&sub) {
+                    new_reaction.add_substrate(sub);
+                }
+            }
+            for prod in &reaction.product {
+                let prod = new_id[*prod as usize];
+                if !new_reaction.substrate.contains(&prod) && !new_reaction.product.contains(&prod)
+                {
+                    new_reaction.add_product(prod);
+                }
+            }
+            if !new_reaction.product.is_empty() {
+                pathway.add_reaction(new_reaction);
+            }
+        }
+
+        *self = pathway;
+        map
+    }
+}
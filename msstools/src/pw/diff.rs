@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pw::Pathway;
+
+/// Reazione con le molecole indicate per nome e ordinate
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DiffReaction {
+    pub name: String,
+    pub substrates: Vec<String>,
+    pub products: Vec<String>,
+}
+
+/// Reazione presente in entrambi i pathway con reagenti o prodotti diversi
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReactionChange {
+    pub reaction: String,
+    pub added_substrates: Vec<String>,
+    pub removed_substrates: Vec<String>,
+    pub added_products: Vec<String>,
+    pub removed_products: Vec<String>,
+}
+
+/// Differenze tra due pathway, con molecole e reazioni confrontate per nome
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathwayDiff {
+    pub added_compounds: Vec<String>,
+    pub removed_compounds: Vec<String>,
+    pub added_reactions: Vec<DiffReaction>,
+    pub removed_reactions: Vec<DiffReaction>,
+    pub changed_reactions: Vec<ReactionChange>,
+}
+
+impl PathwayDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_compounds.is_empty()
+            && self.removed_compounds.is_empty()
+            && self.added_reactions.is_empty()
+            && self.removed_reactions.is_empty()
+            && self.changed_reactions.is_empty()
+    }
+}
+
+/// Confronto tra due seed set dello stesso pathway
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeedSetDiff {
+    pub common: Vec<String>,
+    pub only_first: Vec<String>,
+    pub only_second: Vec<String>,
+
+    /// Molecole raggiunte da ciascun seed set
+    pub first_reached: usize,
+    pub second_reached: usize,
+
+    pub reached_only_first: Vec<String>,
+    pub reached_only_second: Vec<String>,
+}
+
+/// Nomi presenti solo nel nuovo insieme e solo nel vecchio, ordinati
+fn set_diff(old: &BTreeSet<&str>, new: &BTreeSet<&str>) -> (Vec<String>, Vec<String>) {
+    (
+        new.difference(old).map(|s| s.to_string()).collect(),
+        old.difference(new).map(|s| s.to_string()).collect(),
+    )
+}
+
+fn name_set(names: &[String]) -> BTreeSet<&str> {
+    names.iter().map(String::as_str).collect()
+}
+
+/// Reazioni raggruppate per nome, ognuna con le molecole ordinate
+fn reactions_by_name(pathway: &Pathway) -> BTreeMap<String, Vec<DiffReaction>> {
+    let compounds = pathway.get_compounds();
+    let names = |side: &[u32]| -> Vec<String> {
+        let mut names: Vec<String> = side
+            .iter()
+            .map(|c| compounds[*c as usize].name.clone())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let mut reactions = BTreeMap::<String, Vec<DiffReaction>>::new();
+    for reaction in pathway.get_reactions() {
+        reactions
+            .entry(reaction.get_name().clone())
+            .or_default()
+            .push(DiffReaction {
+                name: reaction.get_name().clone(),
+                substrates: names(reaction.get_substrate()),
+                products: names(reaction.get_product()),
+            });
+    }
+    reactions
+}
+
+/// Confronta due pathway per nome. Le reazioni sono confrontate come
+/// multiinsieme di (nome, reagenti, prodotti), quindi quelle con lo stesso
+/// nome (ad esempio le "merged") non si sovrappongono; una reazione e'
+/// considerata modificata solo se e' l'unica con quel nome a cambiare.
+pub fn diff_pathways(old: &Pathway, new: &Pathway) -> PathwayDiff {
+    let compound_names = |pathway: &Pathway| -> BTreeSet<String> {
+        pathway
+            .get_compounds()
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    };
+    let old_compounds = compound_names(old);
+    let new_compounds = compound_names(new);
+
+    let old_reactions = reactions_by_name(old);
+    let mut new_reactions = reactions_by_name(new);
+
+    let mut added_reactions = vec![];
+    let mut removed_reactions = vec![];
+    let mut changed_reactions = vec![];
+    for (name, old_list) in old_reactions {
+        let mut new_list = new_reactions.remove(&name).unwrap_or_default();
+
+        let mut old_only = vec![];
+        for reaction in old_list {
+            match new_list.iter().position(|r| *r == reaction) {
+                Some(i) => {
+                    new_list.swap_remove(i);
+                }
+                None => old_only.push(reaction),
+            }
+        }
+
+        if let ([old_reaction], [new_reaction]) = (old_only.as_slice(), new_list.as_slice()) {
+            let (added_substrates, removed_substrates) = set_diff(
+                &name_set(&old_reaction.substrates),
+                &name_set(&new_reaction.substrates),
+            );
+            let (added_products, removed_products) = set_diff(
+                &name_set(&old_reaction.products),
+                &name_set(&new_reaction.products),
+            );
+
+            // se differiscono solo le molecole ripetute la reazione risulta
+            // rimossa e aggiunta
+            if !(added_substrates.is_empty()
+                && removed_substrates.is_empty()
+                && added_products.is_empty()
+                && removed_products.is_empty())
+            {
+                changed_reactions.push(ReactionChange {
+                    reaction: name,
+                    added_substrates,
+                    removed_substrates,
+                    added_products,
+                    removed_products,
+                });
+                continue;
+            }
+        }
+
+        removed_reactions.extend(old_only);
+        added_reactions.extend(new_list);
+    }
+    added_reactions.extend(new_reactions.into_values().flatten());
+    added_reactions.sort();
+    removed_reactions.sort();
+
+    PathwayDiff {
+        added_compounds: new_compounds.difference(&old_compounds).cloned().collect(),
+        removed_compounds: old_compounds.difference(&new_compounds).cloned().collect(),
+        added_reactions,
+        removed_reactions,
+        changed_reactions,
+    }
+}
+
+/// Confronta due seed set e le molecole che raggiungono
+pub fn diff_seed_sets(
+    pathway: &Pathway,
+    first: &HashSet<u32>,
+    second: &HashSet<u32>,
+) -> SeedSetDiff {
+    let first_closure = pathway.closure(first);
+    let second_closure = pathway.closure(second);
+    let all = 0..pathway.get_compounds_count() as u32;
+
+    SeedSetDiff {
+        common: sorted_names(pathway, first.intersection(second).copied()),
+        only_first: sorted_names(pathway, first.difference(second).copied()),
+        only_second: sorted_names(pathway, second.difference(first).copied()),
+        first_reached: first_closure.get_reached_count(),
+        second_reached: second_closure.get_reached_count(),
+        reached_only_first: sorted_names(
+            pathway,
+            all.clone()
+                .filter(|c| first_closure.is_reached(*c) && !second_closure.is_reached(*c)),
+        ),
+        reached_only_second: sorted_names(
+            pathway,
+            all.filter(|c| second_closure.is_reached(*c) && !first_closure.is_reached(*c)),
+        ),
+    }
+}
+
+fn sorted_names(pathway: &Pathway, ids: impl Iterator<Item = u32>) -> Vec<String> {
+    let compounds = pathway.get_compounds();
+    let mut names: Vec<String> = ids.map(|i| compounds[i as usize].name.clone()).collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pw::{Closure, Pathway};
+
+/// Molecola non raggiunta con le reazioni che potrebbero produrla
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockedCompound {
+    pub compound: u32,
+
+    /// Reazioni che producono la molecola (vuoto se non ne esistono)
+    pub producers: Vec<BlockedReaction>,
+
+    /// La molecola e' gia' stata espansa in un altro punto dell'albero
+    pub repeated: bool,
+}
+
+/// Reazione bloccata dai reagenti non raggiunti
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockedReaction {
+    pub reaction: u32,
+    pub missing: Vec<BlockedCompound>,
+}
+
+/// Spiegazione delle molecole non raggiunte da un seed set
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Explanation {
+    /// Alberi delle dipendenze mancanti; ogni molecola viene espansa una
+    /// sola volta
+    pub trees: Vec<BlockedCompound>,
+
+    /// Molecole non raggiunte che nessuna reazione produce
+    pub frontier: Vec<u32>,
+}
+
+/// Costruisce gli alberi delle dipendenze mancanti a partire dalle molecole
+/// non raggiunte che non servono a nessun'altra molecola non raggiunta
+pub fn explain_unreached(pathway: &Pathway, closure: &Closure) -> Explanation {
+    let cs = pathway.get_compounds_count();
+    let mut producers = vec![Vec::<u32>::new(); cs];
+    for reaction in pathway.get_reactions() {
+        for p in reaction.get_product() {
+            producers[*p as usize].push(reaction.get_id());
+        }
+    }
+
+    let unreached = closure.get_unreached();
+
+    // compounds missing for some other unreached compound
+    let mut needed = HashSet::new();
+    for compound in &unreached {
+        for r in &producers[*compound as usize] {
+            for s in pathway.get_reactions()[*r as usize].get_substrate() {
+                if !closure.is_reached(*s) {
+                    needed.insert(*s);
+                }
+            }
+        }
+    }
+
+    let roots = unreached
+        .iter()
+        .filter(|c| !needed.contains(c))
+        .chain(unreached.iter().filter(|c| needed.contains(c)));
+
+    let mut expanded = HashSet::new();
+    let mut trees = vec![];
+    for root in roots {
+        if !expanded.contains(root) {
+            trees.push(expand(pathway, closure, &producers, *root, &mut expanded));
+        }
+    }
+
+    let frontier = unreached
+        .iter()
+        .filter(|c| producers[**c as usize].is_empty())
+        .copied()
+        .collect();
+
+    Explanation { trees, frontier }
+}
+
+fn expand(
+    pathway: &Pathway,
+    closure: &Closure,
+    producers: &[Vec<u32>],
+    compound: u32,
+    expanded: &mut HashSet<u32>,
+) -> BlockedCompound {
+    if !expanded.insert(compound) {
+        return BlockedCompound {
+            compound,
+            producers: vec![],
+            repeated: true,
+        };
+    }
+
+    let blocked = producers[compound as usize]
+        .iter()
+        .map(|r| {
+            let missing = pathway.get_reactions()[*r as usize]
+                .get_substrate()
+                .iter()
+                .filter(|s| !closure.is_reached(**s))
+                .map(|s| expand(pathway, closure, producers, *s, expanded))
+                .collect();
+            BlockedReaction {
+                reaction: *r,
+                missing,
+            }
+        })
+        .collect();
+
+    BlockedCompound {
+        compound,
+        producers: blocked,
+        repeated: false,
+    }
+}
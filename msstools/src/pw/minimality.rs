@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::mssmodel::Incidence;
+use crate::pw::Pathway;
+
+/// Scambio di un seed con una molecola che non lo e': il nuovo insieme
+/// raggiunge ancora tutto il pathway e contiene un seed ridondante
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SeedSwap {
+    /// Seed rimosso
+    pub removed: u32,
+
+    /// Molecola aggiunta al suo posto
+    pub added: u32,
+
+    /// Seed che dopo lo scambio diventa ridondante
+    pub redundant: u32,
+}
+
+/// Risultato della verifica di minimalita' di un seed set completo
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinimalityReport {
+    /// Seed la cui rimozione lascia il seed set completo
+    pub redundant: Vec<u32>,
+
+    /// Scambi che portano ad un seed set piu' piccolo
+    pub swaps: Vec<SeedSwap>,
+}
+
+impl MinimalityReport {
+    pub fn is_minimal(&self) -> bool {
+        self.redundant.is_empty() && self.swaps.is_empty()
+    }
+}
+
+/// Molecole da cui si puo' arrivare a `compound` (compresa se stessa),
+/// visitando all'indietro reazioni e reagenti
+fn ancestors(incidence: &Incidence, compound: u32) -> HashSet<u32> {
+    let mut visited = HashSet::from([compound]);
+    let mut queue = vec![compound];
+    while let Some(c) = queue.pop() {
+        for reaction in &incidence.comp_produced_by_reac[c as usize] {
+            for sub in &incidence.reac_requires_comp[*reaction as usize] {
+                if visited.insert(*sub) {
+                    queue.push(*sub);
+                }
+            }
+        }
+    }
+    visited
+}
+
+/// Verifica che nessun seed sia ridondante e che nessuno scambio
+/// uno-dentro/uno-fuori renda un seed ridondante (cioe' che non esista un
+/// seed set completo con due seed in meno e uno in piu').
+/// Lo scambio viene cercato solo se non ci sono seed ridondanti.
+///
+/// Senza seed ridondanti, togliendo due seed nessuno dei due viene piu'
+/// raggiunto, quindi la molecola aggiunta deve poterli produrre entrambi:
+/// i candidati sono gli antenati comuni dei due seed e la chiusura viene
+/// calcolata solo per le coppie che ne hanno.
+pub fn check_minimality(pathway: &Pathway, seeds: &HashSet<u32>) -> MinimalityReport {
+    let mut sorted: Vec<u32> = seeds.iter().copied().collect();
+    sorted.sort();
+
+    let without = |removed: &[u32]| -> HashSet<u32> {
+        sorted
+            .iter()
+            .filter(|s| !removed.contains(s))
+            .copied()
+            .collect()
+    };
+
+    let redundant: Vec<u32> = sorted
+        .iter()
+        .filter(|s| pathway.closure(&without(&[**s])).is_complete())
+        .copied()
+        .collect();
+
+    let mut swaps = vec![];
+    if redundant.is_empty() {
+        let incidence = Incidence::new(pathway);
+        let seed_ancestors: Vec<HashSet<u32>> =
+            sorted.iter().map(|s| ancestors(&incidence, *s)).collect();
+
+        for (k, removed) in sorted.iter().enumerate() {
+            for (l, other) in sorted.iter().enumerate().skip(k + 1) {
+                let (small, large) = if seed_ancestors[k].len() <= seed_ancestors[l].len() {
+                    (&seed_ancestors[k], &seed_ancestors[l])
+                } else {
+                    (&seed_ancestors[l], &seed_ancestors[k])
+                };
+                let mut candidates: Vec<u32> = small
+                    .iter()
+                    .filter(|c| large.contains(c) && !seeds.contains(c))
+                    .copied()
+                    .collect();
+                if candidates.is_empty() {
+                    continue;
+                }
+                candidates.sort();
+
+                let base = without(&[*removed, *other]);
+                let closure = pathway.closure(&base);
+
+                // only a compound not reached by the others can complete the set
+                for added in candidates {
+                    if closure.is_reached(added) {
+                        continue;
+                    }
+                    let mut candidate = base.clone();
+                    candidate.insert(added);
+                    if pathway.closure(&candidate).is_complete() {
+                        swaps.push(SeedSwap {
+                            removed: *removed,
+                            added,
+                            redundant: *other,
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    MinimalityReport { redundant, swaps }
+}
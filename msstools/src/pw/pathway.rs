@@ -1,7 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use crate::pw::closure::Closure;
 use crate::pw::compound::Compound;
 use crate::pw::reaction::Reaction;
+use crate::pw::stats::PathwayStats;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +18,12 @@ pub struct Pathway {
     reactions: Vec<Reaction>,
 }
 
+impl Default for Pathway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Pathway {
     pub fn new() -> Self {
         Pathway {
@@ -55,10 +63,92 @@ impl Pathway {
         self.reactions.len()
     }
 
+    pub fn get_compounds(&self) -> &Vec<Compound> {
+        &self.compounds
+    }
+
     pub fn get_reactions(&self) -> &Vec<Reaction> {
         &self.reactions
     }
 
+    /// Calcola le molecole raggiungibili a partire dai seed
+    pub fn closure(&self, seeds: &HashSet<u32>) -> Closure {
+        Closure::new(self, seeds)
+    }
+
+    /// Statistiche di rete del pathway
+    pub fn stats(&self) -> PathwayStats {
+        PathwayStats::new(self)
+    }
+
+    /// Rimuove le molecole da tutte le reazioni, eliminando quelle che
+    /// restano senza prodotti. Restituisce il numero di molecole rimosse.
+    /// WARNING: This changes the IDs of the compounds and of the reactions!
+    pub fn remove_compounds(&mut self, compounds: &HashSet<u32>) -> u32 {
+        let before = self.compounds.len();
+        let mut new_id = HashMap::<u32, u32>::new();
+        let mut new_compounds = vec![];
+        for mut compound in self.compounds.drain(..) {
+            if compounds.contains(&compound.id) {
+                debug!("Removing compound {:?}", compound);
+                continue;
+            }
+            new_id.insert(compound.id, new_compounds.len() as u32);
+            compound.id = new_compounds.len() as u32;
+            new_compounds.push(compound);
+        }
+        let removed = (before - new_compounds.len()) as u32;
+        self.compounds = new_compounds;
+
+        let mut new_reactions = vec![];
+        for mut reaction in self.reactions.drain(..) {
+            reaction.substrate = reaction
+                .substrate
+                .iter()
+                .filter_map(|s| new_id.get(s).copied())
+                .collect();
+            reaction.product = reaction
+                .product
+                .iter()
+                .filter_map(|p| new_id.get(p).copied())
+                .collect();
+            if reaction.product.is_empty() {
+                debug!("Removing {:?} ------ no product left", reaction);
+                continue;
+            }
+            reaction.id = new_reactions.len() as u32;
+            new_reactions.push(reaction);
+        }
+        self.reactions = new_reactions;
+
+        removed
+    }
+
+    /// Molecole "currency" (ATP, NADH, H2O...): quelle nella lista dei nomi
+    /// e quelle che compaiono in piu' di `max_degree` reazioni
+    pub fn currency_compounds(
+        &self,
+        names: &HashSet<String>,
+        max_degree: Option<usize>,
+    ) -> HashSet<u32> {
+        let mut degree = vec![0usize; self.compounds.len()];
+        for reaction in &self.reactions {
+            let touched: HashSet<&u32> =
+                reaction.substrate.iter().chain(&reaction.product).collect();
+            for compound in touched {
+                degree[*compound as usize] += 1;
+            }
+        }
+
+        self.compounds
+            .iter()
+            .filter(|c| {
+                names.contains(&c.name) || max_degree.is_some_and(|max| degree[c.id as usize] > max)
+            })
+            .map(|c| c.id)
+            .collect()
+    }
+
     /// WARNING: This changes the IDs of the reactions!
     pub fn split_multiple_product(&mut self) -> u32 {
         let mut reaction_counter = 0;
@@ -71,13 +161,13 @@ impl Pathway {
             for product in reaction.get_product() {
                 let mut new_reac = Reaction::new(
                     reaction_counter,
-                    format!("{}_{}", reaction.get_name(), product.to_string()),
+                    format!("{}_{}", reaction.get_name(), product),
                 );
                 reaction_counter += 1;
                 for substrate in reaction.get_substrate() {
-                    new_reac.add_substrate(substrate.clone());
+                    new_reac.add_substrate(*substrate);
                 }
-                new_reac.add_product(product.clone());
+                new_reac.add_product(*product);
                 new_reactions.push(new_reac);
             }
         }
@@ -96,7 +186,7 @@ impl Pathway {
         while let Some(mut reaction) = self.reactions.pop() {
             let mut dup = false;
             for ins in &new_reactions {
-                if reaction.has_same_product(&ins) && reaction.has_same_substrate(&ins) {
+                if reaction.has_same_product(ins) && reaction.has_same_substrate(ins) {
                     debug!("Removing {:?} ------ duplicate of {:?}", reaction, ins);
                     dup = true;
                     dup_count += 1;
@@ -212,19 +302,19 @@ impl Pathway {
                     continue;
                 }
                 // I have to find a reaction with the same substrate
-                if reaction.has_same_substrate(&ins) {
+                if reaction.has_same_substrate(ins) {
                     used.insert(ins.id);
                     let mut new_reac = Reaction::new(id_counter, "merged".to_string());
                     id_counter += 1;
                     for sub in &reaction.substrate {
-                        new_reac.add_substrate(sub.clone());
+                        new_reac.add_substrate(*sub);
                     }
                     for prod in &reaction.product {
-                        new_reac.add_product(prod.clone());
+                        new_reac.add_product(*prod);
                     }
                     for prod in &ins.product {
                         if !new_reac.product.contains(prod) {
-                            new_reac.product.push(prod.clone());
+                            new_reac.product.push(*prod);
                         }
                     }
                     debug!("Merging {:?} and {:?} into {:?}", reaction, ins, new_reac);
@@ -87,7 +87,7 @@ impl Reaction {
         let mut vec2 = other.substrate.to_vec();
         vec1.sort();
         vec2.sort();
-        return vec1 == vec2;
+        vec1 == vec2
     }
 
     pub fn has_same_product(&self, other: &Self) -> bool {
@@ -95,6 +95,6 @@ impl Reaction {
         let mut vec2 = other.product.to_vec();
         vec1.sort();
         vec2.sort();
-        return vec1 == vec2;
+        vec1 == vec2
     }
 }
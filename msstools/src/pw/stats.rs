@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::mssmodel::Incidence;
+use crate::pw::Pathway;
+
+/// Distribuzione dei gradi: grado -> numero di nodi con quel grado
+pub type Distribution = BTreeMap<usize, usize>;
+
+/// Statistiche di rete di un pathway
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathwayStats {
+    pub compounds: usize,
+    pub reactions: usize,
+
+    /// Reazioni con piu' di un reagente
+    pub multi_substrate_reactions: usize,
+
+    /// Reazioni con piu' di un prodotto
+    pub multi_product_reactions: usize,
+
+    /// Reazioni che hanno una molecola sia tra i reagenti che tra i prodotti
+    pub self_loop_reactions: usize,
+
+    /// Molecole che nessuna reazione produce
+    pub compounds_without_producer: usize,
+
+    /// Molecole che nessuna reazione consuma
+    pub compounds_without_consumer: usize,
+
+    /// Numero di reazioni che producono ogni molecola
+    pub compound_in_degree: Distribution,
+
+    /// Numero di reazioni che consumano ogni molecola
+    pub compound_out_degree: Distribution,
+
+    /// Numero di reagenti di ogni reazione
+    pub reaction_substrates: Distribution,
+
+    /// Numero di prodotti di ogni reazione
+    pub reaction_products: Distribution,
+
+    /// Dimensioni delle componenti fortemente connesse del grafo delle
+    /// dipendenze tra molecole
+    pub scc_sizes: Distribution,
+}
+
+impl PathwayStats {
+    pub fn new(pathway: &Pathway) -> Self {
+        let cs = pathway.get_compounds_count();
+        let mut produced_by = vec![0usize; cs];
+        let mut consumed_by = vec![0usize; cs];
+
+        let mut stats = PathwayStats {
+            compounds: cs,
+            reactions: pathway.get_reactions_count(),
+            ..Default::default()
+        };
+
+        for reaction in pathway.get_reactions() {
+            let substrate = reaction.get_substrate();
+            let product = reaction.get_product();
+
+            for sub in substrate {
+                consumed_by[*sub as usize] += 1;
+            }
+            for prod in product {
+                produced_by[*prod as usize] += 1;
+            }
+
+            if substrate.len() > 1 {
+                stats.multi_substrate_reactions += 1;
+            }
+            if product.len() > 1 {
+                stats.multi_product_reactions += 1;
+            }
+            if substrate.iter().any(|s| product.contains(s)) {
+                stats.self_loop_reactions += 1;
+            }
+
+            *stats
+                .reaction_substrates
+                .entry(substrate.len())
+                .or_default() += 1;
+            *stats.reaction_products.entry(product.len()).or_default() += 1;
+        }
+
+        stats.compounds_without_producer = produced_by.iter().filter(|d| **d == 0).count();
+        stats.compounds_without_consumer = consumed_by.iter().filter(|d| **d == 0).count();
+
+        for degree in produced_by {
+            *stats.compound_in_degree.entry(degree).or_default() += 1;
+        }
+        for degree in consumed_by {
+            *stats.compound_out_degree.entry(degree).or_default() += 1;
+        }
+
+        for scc in Incidence::new(pathway).compound_sccs() {
+            *stats.scc_sizes.entry(scc.len()).or_default() += 1;
+        }
+
+        stats
+    }
+
+    /// Componenti fortemente connesse con piu' di una molecola
+    pub fn cyclic_sccs(&self) -> usize {
+        self.scc_sizes
+            .iter()
+            .filter(|(size, _)| **size > 1)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    pub fn largest_scc(&self) -> usize {
+        self.scc_sizes.keys().last().copied().unwrap_or(0)
+    }
+}
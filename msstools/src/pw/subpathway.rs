@@ -0,0 +1,116 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::pw::{Compound, Pathway, Reaction};
+
+impl Pathway {
+    /// Sotto-pathway indotto dalle molecole date: restano le reazioni con
+    /// tutti i reagenti nell'insieme, limitate ai prodotti nell'insieme
+    pub fn induced_by_compounds(&self, compounds: &HashSet<u32>) -> Pathway {
+        let reactions = self
+            .get_reactions()
+            .iter()
+            .filter(|r| r.get_substrate().iter().all(|s| compounds.contains(s)))
+            .filter_map(|r| {
+                let product: Vec<u32> = r
+                    .get_product()
+                    .iter()
+                    .copied()
+                    .filter(|p| compounds.contains(p))
+                    .collect();
+                (!product.is_empty()).then(|| (r, r.get_substrate().clone(), product))
+            })
+            .collect();
+
+        self.extract(compounds.iter().copied().collect(), reactions)
+    }
+
+    /// Sotto-pathway con le reazioni date e tutte le molecole che toccano
+    pub fn induced_by_reactions(&self, reactions: &HashSet<u32>) -> Pathway {
+        self.with_reactions(&BTreeSet::new(), reactions)
+    }
+
+    /// Cono all'indietro dei target: le reazioni che li producono, quelle che
+    /// producono i loro reagenti e cosi' via, fino a `steps` livelli (None
+    /// per il cono completo)
+    pub fn upstream(&self, targets: &HashSet<u32>, steps: Option<u32>) -> Pathway {
+        let mut producers = vec![Vec::<u32>::new(); self.get_compounds_count()];
+        for reaction in self.get_reactions() {
+            for prod in reaction.get_product() {
+                producers[*prod as usize].push(reaction.get_id());
+            }
+        }
+
+        let mut visited: HashSet<u32> = targets.clone();
+        let mut frontier: Vec<u32> = targets.iter().copied().collect();
+        let mut reactions = HashSet::new();
+        let mut level = 0;
+
+        while !frontier.is_empty() && steps.is_none_or(|k| level < k) {
+            level += 1;
+            let mut next = vec![];
+            for compound in frontier {
+                for reaction in &producers[compound as usize] {
+                    if !reactions.insert(*reaction) {
+                        continue;
+                    }
+                    for sub in self.get_reactions()[*reaction as usize].get_substrate() {
+                        if visited.insert(*sub) {
+                            next.push(*sub);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        self.with_reactions(&targets.iter().copied().collect(), &reactions)
+    }
+
+    fn with_reactions(&self, extra: &BTreeSet<u32>, reactions: &HashSet<u32>) -> Pathway {
+        let mut compounds = extra.clone();
+        let reactions = self
+            .get_reactions()
+            .iter()
+            .filter(|r| reactions.contains(&r.get_id()))
+            .map(|r| {
+                compounds.extend(r.get_substrate());
+                compounds.extend(r.get_product());
+                (r, r.get_substrate().clone(), r.get_product().clone())
+            })
+            .collect();
+
+        self.extract(compounds, reactions)
+    }
+
+    /// Costruisce il sotto-pathway rinumerando molecole e reazioni
+    /// nell'ordine originale e mantenendo i nomi
+    fn extract(
+        &self,
+        compounds: BTreeSet<u32>,
+        reactions: Vec<(&Reaction, Vec<u32>, Vec<u32>)>,
+    ) -> Pathway {
+        let mut new_id = vec![None; self.get_compounds_count()];
+        let mut pathway = Pathway::new();
+
+        for (id, compound) in compounds.into_iter().enumerate() {
+            new_id[compound as usize] = Some(id as u32);
+            pathway.add_compound(Compound::new(
+                id as u32,
+                self.get_compounds()[compound as usize].name.clone(),
+            ));
+        }
+
+        for (id, (reaction, substrate, product)) in reactions.into_iter().enumerate() {
+            let mut new_reaction = Reaction::new(id as u32, reaction.get_name().clone());
+            for sub in substrate {
+                new_reaction.add_substrate(new_id[sub as usize].unwrap());
+            }
+            for prod in product {
+                new_reaction.add_product(new_id[prod as usize].unwrap());
+            }
+            pathway.add_reaction(new_reaction);
+        }
+
+        pathway
+    }
+}
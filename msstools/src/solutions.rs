@@ -0,0 +1,287 @@
+//! Modulo per la lettura delle soluzioni prodotte dai diversi solver
+//!
+//! Ogni formato viene ricondotto ad un assegnamento delle variabili del
+//! modello oppure, per le liste di seed, direttamente ai nomi dei composti.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Cursor};
+use std::path::Path;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_reader, to_writer_pretty};
+
+use crate::models::mssmodel::ModelVariable;
+use crate::pw::Pathway;
+use crate::solvers::{solver_runner, SolverKind};
+
+mod cplex;
+
+pub use cplex::parse_cplex;
+
+/// Formati di soluzione riconosciuti
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionFormat {
+    Cplex,
+    Gurobi,
+    Cbc,
+    Glpk,
+    Highs,
+    Scip,
+    SeedList,
+}
+
+/// Contenuto di un file di soluzione
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolutionContent {
+    /// Valore di ogni variabile del modello
+    Values(HashMap<String, f64>),
+
+    /// Nomi dei composti che formano il seed set
+    Seeds(Vec<String>),
+}
+
+/// Soluzione letta da file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub content: SolutionContent,
+
+    /// Valore della funzione obiettivo riportato dal solver
+    pub objective: Option<f64>,
+
+    /// Riepilogo degli scarti dei vincoli (solo CPLEX)
+    pub slack: Option<SlackSummary>,
+}
+
+impl Solution {
+    fn from_values(values: HashMap<String, f64>, objective: Option<f64>) -> Self {
+        Solution {
+            content: SolutionContent::Values(values),
+            objective,
+            slack: None,
+        }
+    }
+}
+
+/// Riepilogo degli scarti dei vincoli
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SlackSummary {
+    /// Numero di vincoli
+    pub constraints: usize,
+
+    /// Vincoli con scarto nullo
+    pub binding: usize,
+
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SlackSummary {
+    pub fn new(slacks: &[f64]) -> Option<Self> {
+        if slacks.is_empty() {
+            return None;
+        }
+        Some(SlackSummary {
+            constraints: slacks.len(),
+            binding: slacks.iter().filter(|s| s.abs() < 1e-6).count(),
+            min: slacks.iter().copied().fold(f64::INFINITY, f64::min),
+            max: slacks.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// Riconosce il formato della soluzione dal suo contenuto
+pub fn detect_format(text: &str) -> SolutionFormat {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+
+    if first.starts_with('<') {
+        SolutionFormat::Cplex
+    } else if first.starts_with("# Solution for model") || first.starts_with("# Objective value") {
+        SolutionFormat::Gurobi
+    } else if first.starts_with("Problem:") {
+        SolutionFormat::Glpk
+    } else if first == "Model status" || text.contains("# Columns ") {
+        SolutionFormat::Highs
+    } else if first.starts_with("solution status:") {
+        SolutionFormat::Scip
+    } else if first.contains("objective value") {
+        SolutionFormat::Cbc
+    } else if let Some(pairs) = name_value_pairs(text) {
+        // file di Gurobi senza intestazione, riconosciuti solo se i nomi
+        // sono variabili del modello (x0, u2_1, ...)
+        if pairs.iter().all(|(name, _)| is_model_variable(name))
+            && pairs.iter().any(|(name, _)| name.starts_with('x'))
+        {
+            SolutionFormat::Gurobi
+        } else {
+            warn!("Name/value pairs without model variable names, reading them as a seed list");
+            SolutionFormat::SeedList
+        }
+    } else {
+        SolutionFormat::SeedList
+    }
+}
+
+/// Righe "nome valore" (None se il testo contiene altre righe)
+fn name_value_pairs(text: &str) -> Option<Vec<(&str, f64)>> {
+    let pairs: Option<Vec<(&str, f64)>> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(
+            |l| match l.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [name, value] => value.parse().ok().map(|v| (*name, v)),
+                _ => None,
+            },
+        )
+        .collect();
+    pairs.filter(|p| !p.is_empty())
+}
+
+/// Nomi delle variabili generate dai modelli: lettere seguite da indici
+/// separati da "_" (x0, tm3, u2_1, d1_2)
+fn is_model_variable(name: &str) -> bool {
+    let Some(start) = name.find(|c: char| c.is_ascii_digit()) else {
+        return false;
+    };
+    start > 0
+        && name[..start].chars().all(|c| c.is_ascii_lowercase())
+        && name[start..]
+            .split('_')
+            .all(|i| !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Legge una soluzione nel formato indicato
+pub fn parse_solution(text: &str, format: SolutionFormat) -> Result<Solution, String> {
+    let solver = match format {
+        SolutionFormat::Cplex => return parse_cplex(text),
+        SolutionFormat::Gurobi => {
+            let objective = text
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("# Objective value ="))
+                .and_then(|o| o.trim().parse().ok());
+            return parse_gurobi(text).map(|values| Solution::from_values(values, objective));
+        }
+        SolutionFormat::SeedList => {
+            return Ok(Solution {
+                content: SolutionContent::Seeds(parse_seed_list(text)),
+                objective: None,
+                slack: None,
+            })
+        }
+        SolutionFormat::Cbc => SolverKind::Cbc,
+        SolutionFormat::Glpk => SolverKind::Glpk,
+        SolutionFormat::Highs => SolverKind::Highs,
+        SolutionFormat::Scip => SolverKind::Scip,
+    };
+
+    let solution = solver_runner(solver).read_solution(&mut Cursor::new(text))?;
+    Ok(Solution::from_values(solution.values, solution.objective))
+}
+
+/// Legge un file di soluzione; se il formato non e' indicato viene
+/// riconosciuto dal contenuto
+pub fn read_solution<P: AsRef<Path>>(
+    path: P,
+    format: Option<SolutionFormat>,
+) -> Result<Solution, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Can't open solution: {}", e))?;
+    parse_solution(&text, format.unwrap_or_else(|| detect_format(&text)))
+}
+
+/// File .sol di Gurobi: commenti con '#' e righe "nome valore"
+pub fn parse_gurobi(text: &str) -> Result<HashMap<String, f64>, String> {
+    let mut values = HashMap::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(value)) = (fields.next(), fields.next()) else {
+            return Err(format!("Incorrect Gurobi solution line: {}", line));
+        };
+        let value: f64 = value
+            .parse()
+            .map_err(|_| format!("Incorrect Gurobi solution line: {}", line))?;
+        values.insert(name.to_string(), value);
+    }
+
+    Ok(values)
+}
+
+/// Lista di nomi di composti, uno per riga
+pub fn parse_seed_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Associazione tra le variabili seed del modello e i nomi dei composti
+pub type VariableMap = BTreeMap<String, String>;
+
+/// Ricava dalle variabili del modello il nome del composto di ogni seed
+pub fn seed_variable_map(
+    variables: &HashMap<String, ModelVariable>,
+    pathway: &Pathway,
+) -> VariableMap {
+    let compounds = pathway.get_compounds();
+    variables
+        .iter()
+        .filter_map(|(name, variable)| match variable {
+            ModelVariable::Seed(i) => Some((name.clone(), compounds[*i as usize].name.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn write_variable_map<P: AsRef<Path>>(map: &VariableMap, path: P) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    to_writer_pretty(writer, map)?;
+    Ok(())
+}
+
+pub fn read_variable_map<P: AsRef<Path>>(path: P) -> VariableMap {
+    let file = File::open(path).expect("Can't open variable map");
+    from_reader(BufReader::new(file)).expect("Error parsing variable map")
+}
+
+/// Tolleranza di integralita' predefinita (la stessa di CPLEX)
+pub const DEFAULT_TOLERANCE: f64 = 1e-5;
+
+/// Il valore di una variabile binaria e' 1 a meno della tolleranza
+pub fn is_one(value: f64, tolerance: f64) -> bool {
+    (value - 1.0).abs() <= tolerance
+}
+
+/// Variabili il cui valore dista dall'intero piu' vicino piu' della
+/// tolleranza, ordinate per nome
+pub fn fractional_values(values: &HashMap<String, f64>, tolerance: f64) -> Vec<(String, f64)> {
+    let mut fractional: Vec<(String, f64)> = values
+        .iter()
+        .filter(|(_, value)| (**value - value.round()).abs() > tolerance)
+        .map(|(name, value)| (name.clone(), *value))
+        .collect();
+    fractional.sort_by(|a, b| a.0.cmp(&b.0));
+    fractional
+}
+
+/// Nomi dei composti scelti come seed nella soluzione
+pub fn seed_names(values: &HashMap<String, f64>, map: &VariableMap, tolerance: f64) -> Vec<String> {
+    let mut names: Vec<String> = values
+        .iter()
+        .filter(|(_, value)| is_one(**value, tolerance))
+        .filter_map(|(name, _)| map.get(name).cloned())
+        .collect();
+    names.sort();
+    names
+}
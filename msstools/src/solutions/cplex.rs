@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use serde_xml_rs::from_str;
+
+use crate::solutions::{SlackSummary, Solution as ParsedSolution, SolutionContent};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename = "CPLEXSolution")]
+struct Solution {
+    #[serde(rename = "header")]
+    header: Header,
+    #[serde(rename = "linearConstraints")]
+    constraints: LinearConstraints,
+    #[serde(rename = "variables")]
+    variables: Variables,
+    #[serde(rename = "objectiveValues")]
+    objective: Objective,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Header {
+    #[serde(rename = "problemName")]
+    problem_name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LinearConstraints {
+    #[serde(rename = "constraint")]
+    constraints: Vec<Constraint>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Constraint {
+    #[serde(rename = "name")]
+    name: String,
+    #[serde(rename = "index")]
+    index: usize,
+    #[serde(rename = "slack")]
+    slack: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Variables {
+    #[serde(rename = "variable")]
+    variables: Vec<Variable>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Variable {
+    #[serde(rename = "name")]
+    name: String,
+    #[serde(rename = "index")]
+    index: usize,
+    #[serde(rename = "value")]
+    value: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Objective {
+    #[serde(rename = "objective")]
+    objective: Vec<ObjectiveValue>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ObjectiveValue {
+    #[serde(rename = "name")]
+    name: String,
+    #[serde(rename = "index")]
+    index: usize,
+    #[serde(rename = "value")]
+    value: f64,
+}
+
+/// Legge il file XML scritto da CPLEX
+pub fn parse_cplex(text: &str) -> Result<ParsedSolution, String> {
+    let sol: Solution = from_str(text).map_err(|e| format!("Can't read xml: {}", e))?;
+
+    let objective = sol.objective.objective.first().map(|o| o.value);
+    let slacks: Vec<f64> = sol
+        .constraints
+        .constraints
+        .iter()
+        .map(|c| c.slack)
+        .collect();
+    let values = sol
+        .variables
+        .variables
+        .into_iter()
+        .map(|x| (x.name, x.value))
+        .collect();
+
+    Ok(ParsedSolution {
+        content: SolutionContent::Values(values),
+        objective,
+        slack: SlackSummary::new(&slacks),
+    })
+}
@@ -0,0 +1,208 @@
+//! Modulo per l'esecuzione dei solver MIP installati localmente
+//!
+//! Ogni solver viene lanciato sul file del modello e la sua soluzione,
+//! scritta nel formato nativo, viene riletta come assegnamento delle
+//! variabili.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use log::{info, warn};
+pub use lp_modeler::solvers::Status;
+
+use crate::formats::ModelFormat;
+
+mod cbc;
+mod glpk;
+mod highs;
+mod scip;
+
+pub use cbc::CbcRunner;
+pub use glpk::GlpkRunner;
+pub use highs::HighsRunner;
+pub use scip::ScipRunner;
+
+/// Solver supportati
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverKind {
+    Cbc,
+    Glpk,
+    Highs,
+    Scip,
+}
+
+/// Parametri comuni a tutti i solver
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions {
+    /// Limite di tempo in secondi
+    pub time_limit: Option<u32>,
+
+    /// Numero di thread
+    pub threads: Option<u32>,
+
+    /// Eseguibile da usare al posto di quello predefinito
+    pub command: Option<String>,
+}
+
+/// Soluzione restituita da un solver
+#[derive(Debug, Clone)]
+pub struct SolverSolution {
+    pub status: Status,
+
+    /// Valore della funzione obiettivo (se riportato dal solver)
+    pub objective: Option<f64>,
+
+    /// Valore di ogni variabile
+    pub values: HashMap<String, f64>,
+}
+
+/// Interfaccia comune ai solver eseguiti come processi esterni
+pub trait SolverRunner {
+    /// Nome del solver usato nei log
+    fn name(&self) -> &'static str;
+
+    /// Eseguibile predefinito
+    fn default_command(&self) -> &'static str;
+
+    /// Argomenti da passare all'eseguibile
+    fn arguments(
+        &self,
+        model: &Path,
+        format: ModelFormat,
+        solution: &Path,
+        options: &SolverOptions,
+    ) -> Vec<String>;
+
+    /// Legge il file di soluzione nel formato nativo del solver
+    fn read_solution(&self, reader: &mut dyn BufRead) -> Result<SolverSolution, String>;
+
+    /// File da passare al solver. I solver che riconoscono il formato dal
+    /// nome ricevono una copia del modello con l'estensione corretta se
+    /// quella del file non corrisponde.
+    fn model_path(&self, model: &Path, format: ModelFormat) -> PathBuf {
+        let extension = match format {
+            ModelFormat::Lp => "lp",
+            ModelFormat::Mps | ModelFormat::FreeMps => "mps",
+        };
+        if model.extension().and_then(|e| e.to_str()) == Some(extension) {
+            return model.to_path_buf();
+        }
+        let mut name = model.as_os_str().to_owned();
+        name.push(format!(".{}", extension));
+        PathBuf::from(name)
+    }
+
+    /// File ausiliari (nome e contenuto) da scrivere prima di eseguire il
+    /// solver, ad esempio i file di opzioni; vengono rimossi al termine
+    fn auxiliary_files(
+        &self,
+        _solution: &Path,
+        _options: &SolverOptions,
+    ) -> Vec<(PathBuf, String)> {
+        vec![]
+    }
+
+    /// File in cui il solver scrive la soluzione
+    fn solution_path(&self, model: &Path) -> PathBuf {
+        let mut name = model.as_os_str().to_owned();
+        name.push(format!(".{}.sol", self.default_command()));
+        PathBuf::from(name)
+    }
+
+    /// Risolve il modello contenuto nel file
+    fn run(
+        &self,
+        model: &Path,
+        format: ModelFormat,
+        options: &SolverOptions,
+    ) -> Result<SolverSolution, String> {
+        let solution = self.solution_path(model);
+        let input = self.model_path(model, format);
+        if input != model {
+            fs::copy(model, &input)
+                .map_err(|e| format!("Can't copy the model to {}: {}", input.display(), e))?;
+        }
+        let command = options
+            .command
+            .clone()
+            .unwrap_or_else(|| self.default_command().to_string());
+        let arguments = self.arguments(&input, format, &solution, options);
+
+        let auxiliary = self.auxiliary_files(&solution, options);
+        for (path, contents) in &auxiliary {
+            if let Err(e) = fs::write(path, contents) {
+                remove_files(&input, model, &auxiliary);
+                return Err(format!("Can't write {}: {}", path.display(), e));
+            }
+        }
+
+        info!(
+            "Running {}: {} {}",
+            self.name(),
+            command,
+            arguments.join(" ")
+        );
+
+        let output = Command::new(&command).args(&arguments).output();
+        remove_files(&input, model, &auxiliary);
+        let output =
+            output.map_err(|e| format!("Error running the {} solver: {}", self.name(), e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}: {}",
+                self.name(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let file = File::open(&solution)
+            .map_err(|_| format!("{} did not write {}", self.name(), solution.display()))?;
+        let mut reader = BufReader::new(file);
+        let result = self.read_solution(&mut reader)?;
+
+        info!(
+            "{} finished with status {:?}, objective {:?}",
+            self.name(),
+            result.status,
+            result.objective
+        );
+        Ok(result)
+    }
+}
+
+/// Restituisce l'esecutore per il solver richiesto
+pub fn solver_runner(kind: SolverKind) -> Box<dyn SolverRunner> {
+    match kind {
+        SolverKind::Cbc => Box::new(CbcRunner),
+        SolverKind::Glpk => Box::new(GlpkRunner),
+        SolverKind::Highs => Box::new(HighsRunner),
+        SolverKind::Scip => Box::new(ScipRunner),
+    }
+}
+
+/// Rimuove i file scritti solo per il solver: la copia del modello e i file
+/// ausiliari
+fn remove_files(input: &Path, model: &Path, auxiliary: &[(PathBuf, String)]) {
+    if input != model {
+        let _ = fs::remove_file(input);
+    }
+    for (path, _) in auxiliary {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn warn_unsupported(solver: &str, option: &str) {
+    warn!(
+        "{} does not support the {} option, ignoring it",
+        solver, option
+    );
+}
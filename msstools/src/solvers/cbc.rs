@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::formats::ModelFormat;
+use crate::solvers::{path_string, SolverOptions, SolverRunner, SolverSolution, Status};
+
+/// COIN-OR CBC (`cbc`)
+pub struct CbcRunner;
+
+impl SolverRunner for CbcRunner {
+    fn name(&self) -> &'static str {
+        "CBC"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "cbc"
+    }
+
+    fn arguments(
+        &self,
+        model: &Path,
+        _format: ModelFormat,
+        solution: &Path,
+        options: &SolverOptions,
+    ) -> Vec<String> {
+        let mut args = vec![path_string(model)];
+        if let Some(seconds) = options.time_limit {
+            args.push("sec".to_string());
+            args.push(seconds.to_string());
+        }
+        if let Some(threads) = options.threads {
+            args.push("threads".to_string());
+            args.push(threads.to_string());
+        }
+        args.push("solve".to_string());
+        args.push("solution".to_string());
+        args.push(path_string(solution));
+        args
+    }
+
+    /// Prima riga: stato e obiettivo ("Optimal - objective value 2.0"),
+    /// poi una riga per ogni variabile non nulla: indice, nome, valore,
+    /// costo ridotto (eventualmente precedute da "**")
+    fn read_solution(&self, reader: &mut dyn BufRead) -> Result<SolverSolution, String> {
+        let mut lines = reader.lines().map_while(Result::ok);
+
+        let header = lines
+            .next()
+            .ok_or_else(|| "Empty CBC solution".to_string())?;
+
+        let status = match header.split_whitespace().next() {
+            Some("Optimal") => Status::Optimal,
+            // "Infeasible" or "Integer infeasible"
+            Some("Infeasible") | Some("Integer") => Status::Infeasible,
+            Some("Unbounded") => Status::Unbounded,
+            // "Stopped on time", "on iterations", ...
+            Some("Stopped") => Status::SubOptimal,
+            _ => Status::NotSolved,
+        };
+
+        let objective = header
+            .split("objective value")
+            .nth(1)
+            .and_then(|o| o.split_whitespace().next())
+            .and_then(|o| o.parse().ok());
+
+        let mut values = HashMap::new();
+        for line in lines {
+            let mut fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.first() == Some(&"**") {
+                fields.remove(0);
+            }
+            if fields.len() != 4 {
+                return Err(format!("Incorrect CBC solution line: {}", line));
+            }
+            let value: f64 = fields[2].parse().map_err(|_| line.clone())?;
+            values.insert(fields[1].to_string(), value);
+        }
+
+        Ok(SolverSolution {
+            status,
+            objective,
+            values,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::formats::ModelFormat;
+use crate::solvers::{
+    path_string, warn_unsupported, SolverOptions, SolverRunner, SolverSolution, Status,
+};
+
+/// GLPK (`glpsol`)
+pub struct GlpkRunner;
+
+impl SolverRunner for GlpkRunner {
+    fn name(&self) -> &'static str {
+        "GLPK"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "glpsol"
+    }
+
+    /// glpsol riceve il formato come opzione
+    fn model_path(&self, model: &Path, _format: ModelFormat) -> PathBuf {
+        model.to_path_buf()
+    }
+
+    fn arguments(
+        &self,
+        model: &Path,
+        format: ModelFormat,
+        solution: &Path,
+        options: &SolverOptions,
+    ) -> Vec<String> {
+        let input = match format {
+            ModelFormat::Lp => "--lp",
+            ModelFormat::Mps => "--mps",
+            ModelFormat::FreeMps => "--freemps",
+        };
+        let mut args = vec![input.to_string(), path_string(model)];
+        if let Some(seconds) = options.time_limit {
+            args.push("--tmlim".to_string());
+            args.push(seconds.to_string());
+        }
+        if options.threads.is_some() {
+            warn_unsupported(self.name(), "threads");
+        }
+        args.push("-o".to_string());
+        args.push(path_string(solution));
+        args
+    }
+
+    /// Legge il report stampabile di glpsol (`-o`): lo stato, l'obiettivo
+    /// e la tabella delle colonne. I nomi lunghi occupano una riga a se'
+    /// e i valori si trovano sulla riga successiva.
+    fn read_solution(&self, reader: &mut dyn BufRead) -> Result<SolverSolution, String> {
+        let mut status = Status::NotSolved;
+        let mut objective = None;
+        let mut values = HashMap::new();
+
+        let mut in_columns = false;
+        let mut pending_name: Option<String> = None;
+
+        for line in reader.lines().map_while(Result::ok) {
+            if let Some(s) = line.strip_prefix("Status:") {
+                status = match s.trim() {
+                    "OPTIMAL" | "INTEGER OPTIMAL" => Status::Optimal,
+                    "FEASIBLE" | "INTEGER NON-OPTIMAL" => Status::SubOptimal,
+                    "INFEASIBLE (FINAL)" | "INTEGER EMPTY" | "EMPTY" => Status::Infeasible,
+                    "UNBOUNDED" => Status::Unbounded,
+                    _ => Status::NotSolved,
+                };
+                continue;
+            }
+
+            if let Some(o) = line.strip_prefix("Objective:") {
+                objective = o
+                    .split('=')
+                    .nth(1)
+                    .and_then(|v| v.split_whitespace().next())
+                    .and_then(|v| v.parse().ok());
+                continue;
+            }
+
+            if line.trim_start().starts_with("No. Column name") {
+                in_columns = true;
+                continue;
+            }
+
+            if !in_columns || line.starts_with("------") {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                if pending_name.is_none() {
+                    in_columns = false;
+                }
+                continue;
+            }
+
+            let (name, rest) = match pending_name.take() {
+                Some(name) => (name, &fields[..]),
+                None if fields.len() == 2 => {
+                    pending_name = Some(fields[1].to_string());
+                    continue;
+                }
+                None if fields.len() > 2 => (fields[1].to_string(), &fields[2..]),
+                None => continue,
+            };
+
+            // skip the integer marker or the LP basis status
+            let activity = rest
+                .iter()
+                .find(|f| !matches!(**f, "*" | "B" | "NL" | "NU" | "NF" | "NS"))
+                .ok_or_else(|| format!("Incorrect GLPK solution line: {}", line))?;
+            let value: f64 = activity
+                .parse()
+                .map_err(|_| format!("Incorrect GLPK solution line: {}", line))?;
+            values.insert(name, value);
+        }
+
+        Ok(SolverSolution {
+            status,
+            objective,
+            values,
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::formats::ModelFormat;
+use crate::solvers::{path_string, SolverOptions, SolverRunner, SolverSolution, Status};
+
+/// HiGHS (`highs`)
+pub struct HighsRunner;
+
+/// File di opzioni di HiGHS, accanto al file della soluzione
+fn options_path(solution: &Path) -> PathBuf {
+    let mut name = solution.as_os_str().to_owned();
+    name.push(".options");
+    PathBuf::from(name)
+}
+
+impl SolverRunner for HighsRunner {
+    fn name(&self) -> &'static str {
+        "HiGHS"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "highs"
+    }
+
+    fn arguments(
+        &self,
+        model: &Path,
+        _format: ModelFormat,
+        solution: &Path,
+        options: &SolverOptions,
+    ) -> Vec<String> {
+        let mut args = vec![
+            "--model_file".to_string(),
+            path_string(model),
+            "--solution_file".to_string(),
+            path_string(solution),
+        ];
+        if let Some(seconds) = options.time_limit {
+            args.push("--time_limit".to_string());
+            args.push(seconds.to_string());
+        }
+        // the thread count can only be set through an options file
+        if options.threads.is_some() {
+            args.push("--options_file".to_string());
+            args.push(path_string(&options_path(solution)));
+        }
+        args
+    }
+
+    fn auxiliary_files(&self, solution: &Path, options: &SolverOptions) -> Vec<(PathBuf, String)> {
+        match options.threads {
+            Some(threads) => vec![(options_path(solution), format!("threads = {}\n", threads))],
+            None => vec![],
+        }
+    }
+
+    /// Legge il file di soluzione "raw" di HiGHS: lo stato dopo
+    /// "Model status", l'obiettivo e i valori nella sezione "# Columns N"
+    fn read_solution(&self, reader: &mut dyn BufRead) -> Result<SolverSolution, String> {
+        let mut status = Status::NotSolved;
+        let mut objective = None;
+        let mut values = HashMap::new();
+
+        let mut lines = reader.lines().map_while(Result::ok);
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            if line == "Model status" {
+                let model_status = lines.next().unwrap_or_default();
+                status = match model_status.trim() {
+                    "Optimal" => Status::Optimal,
+                    "Infeasible" => Status::Infeasible,
+                    "Unbounded" | "Primal infeasible or unbounded" => Status::Unbounded,
+                    "Time limit reached" | "Iteration limit reached" | "Interrupted by user" => {
+                        Status::SubOptimal
+                    }
+                    _ => Status::NotSolved,
+                };
+            } else if let Some(o) = line.strip_prefix("Objective ") {
+                objective = o.trim().parse().ok();
+            } else if let Some(count) = line.strip_prefix("# Columns ") {
+                // only the primal values, dual values follow later
+                if !values.is_empty() {
+                    break;
+                }
+                let count: usize = count
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Incorrect HiGHS solution line: {}", line))?;
+                for _ in 0..count {
+                    let entry = lines
+                        .next()
+                        .ok_or_else(|| "Truncated HiGHS solution".to_string())?;
+                    let mut fields = entry.split_whitespace();
+                    let (Some(name), Some(value)) = (fields.next(), fields.next()) else {
+                        return Err(format!("Incorrect HiGHS solution line: {}", entry));
+                    };
+                    let value: f64 = value
+                        .parse()
+                        .map_err(|_| format!("Incorrect HiGHS solution line: {}", entry))?;
+                    values.insert(name.to_string(), value);
+                }
+            }
+        }
+
+        if status == Status::SubOptimal && values.is_empty() {
+            status = Status::NotSolved;
+        }
+
+        Ok(SolverSolution {
+            status,
+            objective,
+            values,
+        })
+    }
+}
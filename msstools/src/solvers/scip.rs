@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::formats::ModelFormat;
+use crate::solvers::{path_string, SolverOptions, SolverRunner, SolverSolution, Status};
+
+/// SCIP (`scip`)
+pub struct ScipRunner;
+
+impl SolverRunner for ScipRunner {
+    fn name(&self) -> &'static str {
+        "SCIP"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "scip"
+    }
+
+    fn arguments(
+        &self,
+        model: &Path,
+        _format: ModelFormat,
+        solution: &Path,
+        options: &SolverOptions,
+    ) -> Vec<String> {
+        let mut args = vec!["-c".to_string(), format!("read {}", path_string(model))];
+        if let Some(seconds) = options.time_limit {
+            args.push("-c".to_string());
+            args.push(format!("set limits time {}", seconds));
+        }
+        // con piu' thread il branch and bound viene eseguito dai solver
+        // concorrenti (richiede SCIP compilato con un TPI)
+        let optimize = match options.threads {
+            Some(threads) => {
+                args.push("-c".to_string());
+                args.push(format!("set parallel maxnthreads {}", threads));
+                args.push("-c".to_string());
+                args.push(format!("set lp threads {}", threads));
+                "concurrentopt"
+            }
+            None => "optimize",
+        };
+        args.push("-c".to_string());
+        args.push(optimize.to_string());
+        args.push("-c".to_string());
+        args.push(format!("write solution {}", path_string(solution)));
+        args.push("-c".to_string());
+        args.push("quit".to_string());
+        args
+    }
+
+    /// Legge il file scritto da "write solution": stato, obiettivo e una
+    /// riga "nome valore (obj:c)" per ogni variabile non nulla
+    fn read_solution(&self, reader: &mut dyn BufRead) -> Result<SolverSolution, String> {
+        let mut status = Status::NotSolved;
+        let mut objective = None;
+        let mut values = HashMap::new();
+
+        for line in reader.lines().map_while(Result::ok) {
+            if let Some(s) = line.strip_prefix("solution status:") {
+                status = match s.trim() {
+                    "optimal solution found" => Status::Optimal,
+                    "infeasible" => Status::Infeasible,
+                    "unbounded" | "infeasible or unbounded" => Status::Unbounded,
+                    s if s.ends_with("limit reached") => Status::SubOptimal,
+                    _ => Status::NotSolved,
+                };
+                continue;
+            }
+
+            if let Some(o) = line.strip_prefix("objective value:") {
+                objective = o.trim().parse().ok();
+                continue;
+            }
+
+            if line.starts_with("no solution available") {
+                status = Status::NotSolved;
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            if let (Some(name), Some(value)) = (fields.next(), fields.next()) {
+                let value: f64 = value
+                    .parse()
+                    .map_err(|_| format!("Incorrect SCIP solution line: {}", line))?;
+                values.insert(name.to_string(), value);
+            }
+        }
+
+        Ok(SolverSolution {
+            status,
+            objective,
+            values,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+use msstools::pw::{Compound, Pathway, Reaction};
+
+/// A -> B -> C
+pub fn chain_pathway() -> Pathway {
+    let mut pathway = Pathway::new();
+    pathway.add_compound(Compound::new(0, "A".to_string()));
+    pathway.add_compound(Compound::new(1, "B".to_string()));
+    pathway.add_compound(Compound::new(2, "C".to_string()));
+
+    let mut reaction1 = Reaction::new(0, "R0".to_string());
+    reaction1.add_substrate(0);
+    reaction1.add_product(1);
+
+    let mut reaction2 = Reaction::new(1, "R1".to_string());
+    reaction2.add_substrate(1);
+    reaction2.add_product(2);
+
+    pathway.add_reaction(reaction1);
+    pathway.add_reaction(reaction2);
+    pathway
+}
@@ -0,0 +1,64 @@
+use msstools::encoders::asp::{parse_clingo_json, write_asp_facts};
+use msstools::encoders::wcnf::{encode_wcnf, parse_maxsat_output};
+
+mod common;
+
+use common::chain_pathway;
+
+#[test]
+fn test_wcnf_encoding() {
+    let pathway = chain_pathway();
+    let encoding = encode_wcnf(&pathway, 2);
+
+    // 3 compounds * 3 instants + 2 reactions * 2 instants
+    assert_eq!(encoding.get_mapping().variables.len(), 13);
+    assert_eq!(encoding.get_soft_count(), 3);
+    // 3 final + 3 * 2 production + 2 * 2 requirement
+    assert_eq!(encoding.get_hard_count(), 13);
+}
+
+#[test]
+fn test_maxsat_output() {
+    let pathway = chain_pathway();
+    let encoding = encode_wcnf(&pathway, 2);
+    let mapping = encoding.get_mapping();
+
+    let literals = "s OPTIMUM FOUND\no 1\nv 1 -4 -7\nv 10 0\n";
+    let values = parse_maxsat_output(literals.as_bytes(), mapping);
+    assert_eq!(values.get("x0"), Some(&1.0));
+    assert_eq!(values.get("x1"), Some(&0.0));
+    assert_eq!(values.get("s0_0"), Some(&1.0));
+
+    let bitstring = "o 1\nv 1000000000000\n";
+    let values = parse_maxsat_output(bitstring.as_bytes(), mapping);
+    assert_eq!(values.get("x0"), Some(&1.0));
+    assert_eq!(values.get("x2"), Some(&0.0));
+}
+
+#[test]
+fn test_asp_facts() {
+    let pathway = chain_pathway();
+    let mut buffer = Vec::<u8>::new();
+    write_asp_facts(&pathway, &mut buffer).unwrap();
+    let facts = String::from_utf8(buffer).unwrap();
+
+    assert!(facts.contains("compound(\"A\")."));
+    assert!(facts.contains("reactant(\"A\",0)."));
+    assert!(facts.contains("product(\"C\",1)."));
+}
+
+#[test]
+fn test_clingo_json() {
+    let output = r#"{
+        "Call": [{ "Witnesses": [
+            { "Value": ["seed(\"A\")", "seed(\"B\")"], "Costs": [2] },
+            { "Value": ["seed(\"(C\\\"1)\")"], "Costs": [1] }
+        ]}],
+        "Result": "OPTIMUM FOUND"
+    }"#;
+
+    assert_eq!(
+        parse_clingo_json(output.as_bytes()),
+        vec!["(C\"1)".to_string()]
+    );
+}
@@ -0,0 +1,435 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
+
+use lp_modeler::dsl::{BoundableLp, LpContinuous, LpInteger, LpObjective, LpOperations, LpProblem};
+use lp_modeler::format::lp_format::LpFileFormat;
+use msstools::formats::graph::{
+    write_dot_to, write_graphml_to, write_node_table_to, write_sif_to, Overlay,
+};
+use msstools::formats::mps::MpsFileFormat;
+use msstools::formats::readable::{write_readable, write_readable_to};
+use msstools::formats::ModelFormat;
+use msstools::models::mssmodel::MssModel;
+use msstools::models::newmodel::NewModel;
+use msstools::parsers::parsereadable::parse_readable;
+use msstools::solvers::{solver_runner, SolverKind, SolverOptions};
+
+mod common;
+
+use common::chain_pathway;
+
+/// Checks every row of a free MPS file against the given point
+fn is_feasible(mps: &str, point: &HashMap<&str, f64>) -> bool {
+    let mut senses = HashMap::<String, String>::new();
+    let mut lhs = HashMap::<String, f64>::new();
+    let mut rhs = HashMap::<String, f64>::new();
+
+    let mut section = "";
+    for line in mps.lines() {
+        if !line.starts_with(' ') {
+            section = line.split_whitespace().next().unwrap();
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match section {
+            "ROWS" if fields[0] != "N" => {
+                senses.insert(fields[1].to_string(), fields[0].to_string());
+            }
+            "COLUMNS" if fields[1] != "'MARKER'" => {
+                let value: f64 = fields[2].parse().unwrap();
+                *lhs.entry(fields[1].to_string()).or_default() +=
+                    value * point.get(fields[0]).copied().unwrap_or(0.0);
+            }
+            "RHS" => {
+                rhs.insert(fields[1].to_string(), fields[2].parse().unwrap());
+            }
+            _ => {}
+        }
+    }
+
+    senses.iter().all(|(row, sense)| {
+        let l = lhs.get(row).copied().unwrap_or(0.0);
+        let r = rhs.get(row).copied().unwrap_or(0.0);
+        match sense.as_str() {
+            "L" => l <= r,
+            "G" => l >= r,
+            _ => l == r,
+        }
+    })
+}
+
+/// Objective, rows (sense, coefficients and rhs), bounds and integer columns
+/// of a model, with the numbers printed to compare them exactly
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ModelSummary {
+    maximize: bool,
+    objective: BTreeMap<String, String>,
+    rows: BTreeMap<String, (char, BTreeMap<String, String>, String)>,
+    bounds: BTreeMap<String, (String, String)>,
+    integers: BTreeSet<String>,
+}
+
+fn number(value: f64) -> String {
+    format!("{}", value)
+}
+
+/// Terms of an LP expression such as "t0 - t1 - 3 x0 + 3 u1_0"
+fn lp_terms(expr: &str) -> BTreeMap<String, String> {
+    let mut terms = BTreeMap::new();
+    let mut sign = 1.0;
+    let mut coef = 1.0;
+    for token in expr.split_whitespace() {
+        match token {
+            "+" => sign = 1.0,
+            "-" => sign = -1.0,
+            _ => match token.parse::<f64>() {
+                Ok(value) => coef = value,
+                Err(_) => {
+                    terms.insert(token.to_string(), number(sign * coef));
+                    sign = 1.0;
+                    coef = 1.0;
+                }
+            },
+        }
+    }
+    terms
+}
+
+fn lp_summary(lp: &str) -> ModelSummary {
+    let mut summary = ModelSummary::default();
+    let mut generals = vec![];
+    let mut section = "";
+    for line in lp.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match line {
+            "Minimize" | "Maximize" | "Subject To" | "Bounds" | "Generals" | "Binary" | "End" => {
+                summary.maximize |= line == "Maximize";
+                section = line;
+                continue;
+            }
+            _ => {}
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match section {
+            "Minimize" | "Maximize" => {
+                summary.objective = lp_terms(line.split_once(':').unwrap().1);
+            }
+            "Subject To" => {
+                let (name, body) = line.split_once(':').unwrap();
+                let (lhs, sense, rhs) = [(" <= ", 'L'), (" >= ", 'G'), (" = ", 'E')]
+                    .iter()
+                    .find_map(|(op, sense)| body.split_once(op).map(|(l, r)| (l, *sense, r)))
+                    .unwrap();
+                let rhs: f64 = rhs.trim().parse().unwrap();
+                summary
+                    .rows
+                    .insert(name.to_string(), (sense, lp_terms(lhs), number(rhs)));
+            }
+            "Bounds" => match fields.as_slice() {
+                [l, "<=", name, "<=", u] => {
+                    summary
+                        .bounds
+                        .insert(name.to_string(), (l.to_string(), u.to_string()));
+                }
+                [name, "free"] => {
+                    summary
+                        .bounds
+                        .insert(name.to_string(), ("-inf".to_string(), "inf".to_string()));
+                }
+                _ => panic!("Unexpected bound {}", line),
+            },
+            "Generals" => generals.extend(fields.iter().map(|f| f.to_string())),
+            "Binary" => {
+                for name in fields {
+                    summary.integers.insert(name.to_string());
+                    summary
+                        .bounds
+                        .insert(name.to_string(), ("0".to_string(), "1".to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    for name in generals {
+        summary.integers.insert(name.clone());
+        summary
+            .bounds
+            .entry(name)
+            .or_insert(("0".to_string(), "inf".to_string()));
+    }
+    summary
+}
+
+fn mps_summary(mps: &str) -> ModelSummary {
+    let mut summary = ModelSummary::default();
+    let mut integer = false;
+    let mut section = "";
+    for line in mps.lines() {
+        if !line.starts_with(' ') {
+            section = line.split_whitespace().next().unwrap();
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match (section, fields.as_slice()) {
+            ("OBJSENSE", ["MAX"]) => summary.maximize = true,
+            ("ROWS", [sense, name]) if *sense != "N" => {
+                let sense = sense.chars().next().unwrap();
+                summary
+                    .rows
+                    .insert(name.to_string(), (sense, BTreeMap::new(), number(0.0)));
+            }
+            ("COLUMNS", [_, "'MARKER'", marker]) => integer = *marker == "'INTORG'",
+            ("COLUMNS", [column, row, value]) => {
+                let value = number(value.parse().unwrap());
+                if integer {
+                    summary.integers.insert(column.to_string());
+                }
+                match summary.rows.get_mut(*row) {
+                    Some((_, coefs, _)) => {
+                        coefs.insert(column.to_string(), value);
+                    }
+                    None => {
+                        summary.objective.insert(column.to_string(), value);
+                    }
+                }
+            }
+            ("RHS", [_, row, value]) => {
+                summary.rows.get_mut(*row).unwrap().2 = number(value.parse().unwrap());
+            }
+            ("BOUNDS", [kind, _, column, rest @ ..]) => {
+                let bound = summary
+                    .bounds
+                    .entry(column.to_string())
+                    .or_insert(("0".to_string(), "inf".to_string()));
+                let value = || number(rest[0].parse().unwrap());
+                match *kind {
+                    "BV" => *bound = ("0".to_string(), "1".to_string()),
+                    "PL" => bound.1 = "inf".to_string(),
+                    "MI" => bound.0 = "-inf".to_string(),
+                    "FR" => *bound = ("-inf".to_string(), "inf".to_string()),
+                    "LO" => bound.0 = value(),
+                    "UP" => bound.1 = value(),
+                    _ => panic!("Unexpected bound {}", line),
+                }
+            }
+            _ => {}
+        }
+    }
+    summary
+}
+
+#[test]
+fn test_mps_export() {
+    let pathway = chain_pathway();
+    let problem = NewModel::new(&pathway, 3).build();
+
+    let mps = problem.to_mps_file_format(true).unwrap();
+    assert!(mps.starts_with("NAME          MSS\nROWS\n N  obj\n"));
+    assert!(mps.ends_with("ENDATA\n"));
+    assert!(mps.contains(" BV BND x0\n"));
+    assert!(mps.contains(" PL BND t0\n"));
+
+    let rows = mps
+        .lines()
+        .skip_while(|l| *l != "ROWS")
+        .take_while(|l| *l != "COLUMNS")
+        .count();
+    // "ROWS" and the objective row
+    assert_eq!(rows, problem.constraints.len() + 2);
+}
+
+#[test]
+fn test_mps_same_feasible_set() {
+    let pathway = chain_pathway();
+    let problem = NewModel::new(&pathway, 3).build();
+    let mps = problem.to_mps_file_format(true).unwrap();
+
+    let mut point = HashMap::new();
+    point.insert("x0", 1.0);
+    point.insert("u1_0", 1.0);
+    point.insert("u2_1", 1.0);
+    point.insert("t0", 0.0);
+    point.insert("t1", 1.0);
+    point.insert("t2", 2.0);
+    assert!(is_feasible(&mps, &point));
+
+    // C activated before B
+    point.insert("t2", 1.0);
+    assert!(!is_feasible(&mps, &point));
+
+    // no seed
+    point.insert("t2", 2.0);
+    point.insert("x0", 0.0);
+    assert!(!is_feasible(&mps, &point));
+}
+
+#[test]
+fn test_mps_bounds_and_sense() {
+    let a = LpInteger::new("a").lower_bound(1.0).upper_bound(4.0);
+    let b = LpContinuous::new("b");
+
+    let mut problem = LpProblem::new("B", LpObjective::Maximize);
+    problem += &a + 2 * &b;
+    problem += (&a + &b).le(10);
+
+    let mps = problem.to_mps_file_format(true).unwrap();
+    assert!(mps.contains("OBJSENSE\n    MAX\n"));
+    assert!(mps.contains(" LO BND a 1\n UP BND a 4\n"));
+    assert!(mps.contains("    b obj 2\n"));
+    assert!(mps.contains("    RHS c1 10\n"));
+}
+
+#[test]
+fn test_mps_inconsistent_bounds() {
+    let a = LpInteger::new("a");
+    let mut problem = LpProblem::new("B", LpObjective::Minimize);
+    problem += &a;
+    problem += a.clone().upper_bound(4.0).ge(1);
+
+    let error = problem.to_mps_file_format(true).unwrap_err();
+    assert!(error.to_string().contains("Variable a"));
+}
+
+#[test]
+fn test_fixed_mps_long_names() {
+    let a = LpInteger::new("a_long_name");
+    let mut problem = LpProblem::new("B", LpObjective::Minimize);
+    problem += &a;
+    problem += a.ge(1);
+
+    assert!(problem.to_mps_file_format(true).is_ok());
+    let error = problem.to_mps_file_format(false).unwrap_err();
+    assert!(error.to_string().contains("a_long_name"));
+}
+
+#[test]
+fn test_mps_same_model_as_lp() {
+    let pathway = chain_pathway();
+    let problem = NewModel::new(&pathway, 3).build();
+    let lp = lp_summary(&problem.to_lp_file_format());
+    assert_eq!(lp.rows.len(), problem.constraints.len());
+
+    assert_eq!(mps_summary(&problem.to_mps_file_format(true).unwrap()), lp);
+    assert_eq!(mps_summary(&problem.to_mps_file_format(false).unwrap()), lp);
+
+    let a = LpInteger::new("a").lower_bound(1.0).upper_bound(4.0);
+    let b = LpContinuous::new("b");
+    let mut problem = LpProblem::new("B", LpObjective::Maximize);
+    problem += &a + 2 * &b;
+    problem += (&a + &b).le(10);
+    problem += (&a - &b).ge(-2);
+
+    let lp = lp_summary(&problem.to_lp_file_format());
+    assert!(lp.maximize);
+    assert_eq!(mps_summary(&problem.to_mps_file_format(true).unwrap()), lp);
+}
+
+/// Solves the LP and the MPS file with every solver found on the PATH
+#[test]
+fn test_mps_same_optimum_as_lp() {
+    let pathway = chain_pathway();
+    let problem = NewModel::new(&pathway, 3).build();
+
+    let dir = env::temp_dir().join(format!("msstools-mps-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let lp_path = dir.join("model.lp");
+    let mps_path = dir.join("model.mps");
+    problem.write_lp(lp_path.to_str().unwrap()).unwrap();
+    problem
+        .write_mps(mps_path.to_str().unwrap(), false)
+        .unwrap();
+
+    let path = env::var_os("PATH").unwrap_or_default();
+    for kind in [
+        SolverKind::Cbc,
+        SolverKind::Glpk,
+        SolverKind::Highs,
+        SolverKind::Scip,
+    ] {
+        let runner = solver_runner(kind);
+        if !env::split_paths(&path).any(|d| d.join(runner.default_command()).is_file()) {
+            eprintln!("{} not found, skipping", runner.name());
+            continue;
+        }
+
+        let options = SolverOptions::default();
+        let lp = runner.run(&lp_path, ModelFormat::Lp, &options).unwrap();
+        let mps = runner.run(&mps_path, ModelFormat::Mps, &options).unwrap();
+        assert_eq!(lp.status, mps.status);
+        assert_eq!(lp.objective, mps.objective);
+        assert_eq!(lp.objective, Some(1.0));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_readable_round_trip() {
+    let pathway = chain_pathway();
+
+    let mut text = Vec::new();
+    write_readable_to(&pathway, &mut text).unwrap();
+    assert_eq!(
+        String::from_utf8(text).unwrap(),
+        "3\nA\nB\nC\n2\nR0\n1\nA\n1\nB\nR1\n1\nB\n1\nC\n"
+    );
+
+    let path = std::env::temp_dir().join("msstools_readable_round_trip.txt");
+    write_readable(&pathway, &path).unwrap();
+    let parsed = parse_readable(path.clone());
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(parsed.get_compounds_count(), 3);
+    assert_eq!(parsed.get_reactions_count(), 2);
+    let b = parsed.get_compound_option(&"B".to_string()).unwrap();
+    let c = parsed.get_compound_option(&"C".to_string()).unwrap();
+    let r1 = &parsed.get_reactions()[1];
+    assert_eq!(r1.get_substrate(), &vec![b]);
+    assert_eq!(r1.get_product(), &vec![c]);
+}
+
+#[test]
+fn test_graph_export() {
+    let pathway = chain_pathway();
+    let overlay = Overlay::new(&pathway, &HashSet::from([1]));
+
+    assert_eq!(overlay.compound_step(2), Some(1));
+    assert_eq!(overlay.compound_step(0), None);
+    assert_eq!(overlay.reaction_step(1), Some(1));
+    assert_eq!(overlay.reaction_step(0), None);
+
+    let frame = overlay.until(0);
+    assert_eq!(frame.compound_step(1), Some(0));
+    assert_eq!(frame.compound_step(2), None);
+    assert!(frame.is_latest(Some(0)));
+    assert!(!overlay.is_latest(Some(0)));
+
+    let mut dot = Vec::new();
+    write_dot_to(&pathway, Some(&overlay), &mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.contains(
+        "c1 [label=\"B\", shape=ellipse, seed=true, style=filled, fillcolor=gold, step=0];"
+    ));
+    assert!(dot.contains("r0 [label=\"R0\", shape=box, style=dashed];"));
+    assert!(dot.contains("  c1 -> r1;\n  r1 -> c2;\n"));
+
+    let mut graphml = Vec::new();
+    write_graphml_to(&pathway, None, &mut graphml).unwrap();
+    let graphml = String::from_utf8(graphml).unwrap();
+    assert!(!graphml.contains("key=\"step\""));
+    assert_eq!(graphml.matches("<node ").count(), 5);
+    assert_eq!(graphml.matches("<edge ").count(), 4);
+
+    let mut sif = Vec::new();
+    write_sif_to(&pathway, &mut sif).unwrap();
+    assert_eq!(
+        String::from_utf8(sif).unwrap(),
+        "c0\tsubstrate_of\tr0\nr0\tproduces\tc1\nc1\tsubstrate_of\tr1\nr1\tproduces\tc2\n"
+    );
+
+    let mut nodes = Vec::new();
+    write_node_table_to(&pathway, Some(&overlay), &mut nodes).unwrap();
+    let nodes = String::from_utf8(nodes).unwrap();
+    assert!(nodes.starts_with("id\tname\ttype\tseed\tstep\nc0\tA\tcompound\tfalse\t\n"));
+    assert!(nodes.contains("\nr1\tR1\treaction\tfalse\t1\n"));
+}
@@ -1,4 +1,11 @@
-use msstools::pw::{Pathway, Reaction};
+use std::collections::{BTreeMap, HashSet};
+
+use msstools::pw::{
+    check_minimality, diff_pathways, diff_seed_sets, explain_unreached, merge_community,
+    BlockedCompound, BlockedReaction, CommunityOptions, Compound, ContractedCompound,
+    ContractionMap, DiffReaction, Pathway, PathwayDiff, PathwayStats, Reaction, ReactionChange,
+    SeedSwap,
+};
 
 #[test]
 fn test_split() {
@@ -63,3 +70,385 @@ fn test_dominated() {
 
     assert_eq!(pathway.get_reactions_count(), 1);
 }
+
+fn branching_pathway() -> Pathway {
+    // A -> B, B -> C, {A, C} -> D
+    let mut pathway = Pathway::new();
+    for (id, name) in ["A", "B", "C", "D"].iter().enumerate() {
+        pathway.add_compound(Compound::new(id as u32, name.to_string()));
+    }
+    let reactions = [
+        (vec![0], vec![1]),
+        (vec![1], vec![2]),
+        (vec![0, 2], vec![3]),
+    ];
+    for (id, (substrate, product)) in reactions.iter().enumerate() {
+        let mut reaction = Reaction::new(id as u32, format!("R{}", id));
+        substrate.iter().for_each(|s| reaction.add_substrate(*s));
+        product.iter().for_each(|p| reaction.add_product(*p));
+        pathway.add_reaction(reaction);
+    }
+    pathway
+}
+
+#[test]
+fn test_closure() {
+    let pathway = branching_pathway();
+
+    let closure = pathway.closure(&HashSet::from([0]));
+    assert!(closure.is_complete());
+    assert_eq!(closure.get_steps(), 3);
+    assert_eq!(closure.get_activation(2), Some(2));
+    assert_eq!(closure.get_producer(3), Some(2));
+    assert_eq!(closure.get_producer(0), None);
+
+    let closure = pathway.closure(&HashSet::from([1]));
+    assert!(!closure.is_complete());
+    assert_eq!(closure.get_unreached(), vec![0, 3]);
+
+    // B is redundant
+    let report = check_minimality(&pathway, &HashSet::from([0, 1]));
+    assert_eq!(report.redundant, vec![1]);
+
+    // replacing B with A makes C redundant
+    let report = check_minimality(&pathway, &HashSet::from([1, 2]));
+    assert!(report.redundant.is_empty());
+    assert_eq!(
+        report.swaps,
+        vec![SeedSwap {
+            removed: 1,
+            added: 0,
+            redundant: 2
+        }]
+    );
+
+    assert!(check_minimality(&pathway, &HashSet::from([0])).is_minimal());
+}
+
+#[test]
+fn test_explain() {
+    let pathway = branching_pathway();
+    let closure = pathway.closure(&HashSet::from([1]));
+    let explanation = explain_unreached(&pathway, &closure);
+
+    let no_producer = |compound| BlockedCompound {
+        compound,
+        producers: vec![],
+        repeated: false,
+    };
+
+    assert_eq!(explanation.frontier, vec![0]);
+    assert_eq!(
+        explanation.trees,
+        vec![BlockedCompound {
+            compound: 3,
+            producers: vec![BlockedReaction {
+                reaction: 2,
+                missing: vec![no_producer(0)]
+            }],
+            repeated: false
+        }]
+    );
+}
+
+#[test]
+fn test_stats() {
+    let mut pathway = branching_pathway();
+
+    // C -> B + C
+    let mut reaction = Reaction::new(3, "R3".to_string());
+    reaction.add_substrate(2);
+    reaction.add_product(1);
+    reaction.add_product(2);
+    pathway.add_reaction(reaction);
+
+    let stats = pathway.stats();
+    assert_eq!(
+        stats,
+        PathwayStats {
+            compounds: 4,
+            reactions: 4,
+            multi_substrate_reactions: 1,
+            multi_product_reactions: 1,
+            self_loop_reactions: 1,
+            compounds_without_producer: 1,
+            compounds_without_consumer: 1,
+            compound_in_degree: BTreeMap::from([(0, 1), (1, 1), (2, 2)]),
+            compound_out_degree: BTreeMap::from([(0, 1), (1, 1), (2, 2)]),
+            reaction_substrates: BTreeMap::from([(1, 3), (2, 1)]),
+            reaction_products: BTreeMap::from([(1, 3), (2, 1)]),
+            scc_sizes: BTreeMap::from([(1, 2), (2, 1)]),
+        }
+    );
+    assert_eq!(stats.cyclic_sccs(), 1);
+    assert_eq!(stats.largest_scc(), 2);
+}
+
+#[test]
+fn test_diff() {
+    let old = branching_pathway();
+
+    // A -> B, {A, E} -> D, B -> E
+    let mut new = Pathway::new();
+    for (id, name) in ["E", "D", "B", "A"].iter().enumerate() {
+        new.add_compound(Compound::new(id as u32, name.to_string()));
+    }
+    let reactions = [
+        ("R0", vec![3], vec![2]),
+        ("R2", vec![0, 3], vec![1]),
+        ("R3", vec![2], vec![0]),
+    ];
+    for (id, (name, substrate, product)) in reactions.iter().enumerate() {
+        let mut reaction = Reaction::new(id as u32, name.to_string());
+        substrate.iter().for_each(|s| reaction.add_substrate(*s));
+        product.iter().for_each(|p| reaction.add_product(*p));
+        new.add_reaction(reaction);
+    }
+
+    assert_eq!(
+        diff_pathways(&old, &new),
+        PathwayDiff {
+            added_compounds: vec!["E".to_string()],
+            removed_compounds: vec!["C".to_string()],
+            added_reactions: vec![DiffReaction {
+                name: "R3".to_string(),
+                substrates: vec!["B".to_string()],
+                products: vec!["E".to_string()],
+            }],
+            removed_reactions: vec![DiffReaction {
+                name: "R1".to_string(),
+                substrates: vec!["B".to_string()],
+                products: vec!["C".to_string()],
+            }],
+            changed_reactions: vec![ReactionChange {
+                reaction: "R2".to_string(),
+                added_substrates: vec!["E".to_string()],
+                removed_substrates: vec!["C".to_string()],
+                ..Default::default()
+            }],
+        }
+    );
+    assert!(diff_pathways(&new, &new).is_empty());
+
+    // reactions with the same name, as after the merge preprocessing
+    let merged = |reactions: &[(u32, u32)]| {
+        let mut pathway = Pathway::new();
+        for (id, name) in ["A", "B", "C"].iter().enumerate() {
+            pathway.add_compound(Compound::new(id as u32, name.to_string()));
+        }
+        for (id, (substrate, product)) in reactions.iter().enumerate() {
+            let mut reaction = Reaction::new(id as u32, "merged".to_string());
+            reaction.add_substrate(*substrate);
+            reaction.add_product(*product);
+            pathway.add_reaction(reaction);
+        }
+        pathway
+    };
+
+    let diff = diff_pathways(&merged(&[(0, 1)]), &merged(&[(0, 1), (1, 2)]));
+    assert_eq!(
+        diff.added_reactions,
+        vec![DiffReaction {
+            name: "merged".to_string(),
+            substrates: vec!["B".to_string()],
+            products: vec!["C".to_string()],
+        }]
+    );
+    assert!(diff.removed_reactions.is_empty() && diff.changed_reactions.is_empty());
+
+    let seeds = diff_seed_sets(&old, &HashSet::from([0]), &HashSet::from([1, 2]));
+    assert_eq!(seeds.only_first, vec!["A"]);
+    assert_eq!(seeds.only_second, vec!["B", "C"]);
+    assert!(seeds.common.is_empty());
+    assert_eq!((seeds.first_reached, seeds.second_reached), (4, 2));
+    assert_eq!(seeds.reached_only_first, vec!["A", "D"]);
+    assert!(seeds.reached_only_second.is_empty());
+}
+
+#[test]
+fn test_community() {
+    let pathway = |names: [&str; 2]| {
+        let mut pathway = Pathway::new();
+        pathway.add_compound(Compound::new(0, names[0].to_string()));
+        pathway.add_compound(Compound::new(1, names[1].to_string()));
+        let mut reaction = Reaction::new(0, "R0".to_string());
+        reaction.add_substrate(0);
+        reaction.add_product(1);
+        pathway.add_reaction(reaction);
+        pathway
+    };
+    let first = pathway(["A", "B"]);
+    let second = pathway(["B", "C"]);
+    let members = [("o1".to_string(), &first), ("o2".to_string(), &second)];
+
+    let community = merge_community(&members, &CommunityOptions::default());
+    assert_eq!(community.get_compounds_count(), 3);
+    assert_eq!(community.get_reactions()[1].get_name(), "o2:R0");
+    assert_eq!(community.get_reactions()[1].get_substrate(), &vec![1]);
+
+    let options = CommunityOptions {
+        compartments: true,
+        exchange: None,
+    };
+    let community = merge_community(&members, &options);
+    let names: Vec<&str> = community
+        .get_compounds()
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["o1:A", "o1:B", "B", "o2:B", "o2:C"]);
+    let names: Vec<&str> = community
+        .get_reactions()
+        .iter()
+        .map(|r| r.get_name().as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "o1:R0",
+            "o1:export:B",
+            "o1:import:B",
+            "o2:R0",
+            "o2:export:B",
+            "o2:import:B"
+        ]
+    );
+    assert!(community.closure(&HashSet::from([0])).is_complete());
+
+    // without exchange the organisms are isolated
+    let options = CommunityOptions {
+        compartments: true,
+        exchange: Some(HashSet::new()),
+    };
+    let community = merge_community(&members, &options);
+    assert_eq!(community.get_compounds_count(), 4);
+    assert_eq!(
+        community.closure(&HashSet::from([0])).get_reached_count(),
+        2
+    );
+}
+
+#[test]
+fn test_subpathway() {
+    let pathway = branching_pathway();
+    let names = |pathway: &Pathway| -> Vec<String> {
+        pathway
+            .get_compounds()
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    };
+
+    let sub = pathway.upstream(&HashSet::from([3]), Some(1));
+    assert_eq!(names(&sub), vec!["A", "C", "D"]);
+    assert_eq!(sub.get_reactions_count(), 1);
+    assert_eq!(sub.get_reactions()[0].get_name(), "R2");
+    assert_eq!(sub.get_reactions()[0].get_substrate(), &vec![0, 1]);
+    assert_eq!(sub.get_reactions()[0].get_product(), &vec![2]);
+
+    let cone = pathway.upstream(&HashSet::from([3]), None);
+    assert_eq!(cone.get_compounds_count(), 4);
+    assert_eq!(cone.get_reactions_count(), 3);
+
+    let sub = pathway.induced_by_compounds(&HashSet::from([0, 1]));
+    assert_eq!(names(&sub), vec!["A", "B"]);
+    assert_eq!(sub.get_reactions()[0].get_name(), "R0");
+    assert_eq!(sub.get_reactions_count(), 1);
+
+    let sub = pathway.induced_by_reactions(&HashSet::from([1]));
+    assert_eq!(names(&sub), vec!["B", "C"]);
+    assert_eq!(sub.get_reactions()[0].get_substrate(), &vec![0]);
+}
+
+#[test]
+fn test_remove_compounds() {
+    let mut pathway = branching_pathway();
+
+    // A, B and C appear in two reactions, D only in one
+    let currency = pathway.currency_compounds(&HashSet::from(["B".to_string()]), Some(1));
+    assert_eq!(currency, HashSet::from([0, 1, 2]));
+
+    // removing B leaves R0 without products
+    assert_eq!(pathway.remove_compounds(&HashSet::from([1])), 1);
+    assert_eq!(pathway.get_compounds_count(), 3);
+    assert_eq!(pathway.get_compounds()[1].name, "C");
+    assert_eq!(pathway.get_compounds()[1].id, 1);
+    assert_eq!(pathway.get_reactions_count(), 2);
+
+    let r1 = &pathway.get_reactions()[0];
+    assert_eq!((r1.get_id(), r1.get_name().as_str()), (0, "R1"));
+    assert!(r1.get_substrate().is_empty());
+    assert_eq!(r1.get_product(), &vec![1]);
+    assert_eq!(pathway.get_reactions()[1].get_substrate(), &vec![0, 1]);
+    assert_eq!(pathway.get_reactions()[1].get_product(), &vec![2]);
+}
+
+/// A <-> B e' un ciclo, C e' prodotto solo da A, E richiede C e D
+fn equivalence_pathway() -> Pathway {
+    let mut pathway = Pathway::new();
+    for (i, name) in ["A", "B", "C", "D", "E"].iter().enumerate() {
+        pathway.add_compound(Compound::new(i as u32, name.to_string()));
+    }
+
+    for (i, (substrate, product)) in [
+        (vec![0], vec![1]),
+        (vec![1], vec![0]),
+        (vec![0], vec![2]),
+        (vec![2, 3], vec![4]),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut reaction = Reaction::new(i as u32, format!("R{}", i));
+        substrate
+            .into_iter()
+            .for_each(|s| reaction.add_substrate(s));
+        product.into_iter().for_each(|p| reaction.add_product(p));
+        pathway.add_reaction(reaction);
+    }
+
+    pathway
+}
+
+#[test]
+fn test_contract_equivalent() {
+    let mut pathway = equivalence_pathway();
+    let original = equivalence_pathway();
+
+    let map = ContractionMap::identity(&pathway).then(&pathway.contract_equivalent());
+    assert_eq!(
+        map.compounds,
+        vec![
+            ContractedCompound {
+                name: "A".to_string(),
+                members: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            },
+            ContractedCompound {
+                name: "D".to_string(),
+                members: vec!["D".to_string()],
+            },
+            ContractedCompound {
+                name: "E".to_string(),
+                members: vec!["E".to_string()],
+            },
+        ]
+    );
+    assert_eq!(map.contracted_count(), 2);
+
+    // only R3 keeps a product outside its substrates
+    assert_eq!(pathway.get_compounds_count(), 3);
+    assert_eq!(pathway.get_reactions_count(), 1);
+    let r3 = &pathway.get_reactions()[0];
+    assert_eq!((r3.get_id(), r3.get_name().as_str()), (0, "R3"));
+    assert_eq!(r3.get_substrate(), &vec![0, 1]);
+    assert_eq!(r3.get_product(), &vec![2]);
+
+    // the representatives of the seeds reach the whole original pathway
+    let seeds = map.expand_seeds(&HashSet::from([0, 1]));
+    assert_eq!(seeds, vec!["A".to_string(), "D".to_string()]);
+    let seeds: HashSet<u32> = seeds
+        .iter()
+        .map(|s| original.get_compound_option(s).unwrap())
+        .collect();
+    assert_eq!(original.closure(&seeds).get_reached_count(), 5);
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use msstools::models::acyclicmodel::AcyclicModel;
+use msstools::models::bigmmodel::BigMModel;
+use msstools::models::mssmodel::{
+    time_violations, ModelVariable, MssModel, SeedSolution, TimeViolation,
+};
+use msstools::models::newmodel::NewModel;
+use msstools::models::timesetmodel::TimeSetModel;
+use msstools::pw::{Compound, Reaction};
+use msstools::solutions::DEFAULT_TOLERANCE;
+
+mod common;
+
+use common::chain_pathway;
+
+#[test]
+fn test_variables_mapping() {
+    let pathway = chain_pathway();
+    let model = NewModel::new(&pathway, 3);
+    let variables = model.variables();
+
+    assert_eq!(variables.get("x2"), Some(&ModelVariable::Seed(2)));
+    assert_eq!(variables.get("t1"), Some(&ModelVariable::CompoundTime(1)));
+    assert_eq!(
+        variables.get("u2_1"),
+        Some(&ModelVariable::Production(2, 1))
+    );
+    assert_eq!(variables.get("u0_0"), None);
+}
+
+#[test]
+fn test_decode_timeset() {
+    let pathway = chain_pathway();
+    let model = TimeSetModel::new(&pathway, 4);
+
+    let mut values = HashMap::new();
+    values.insert("x0".to_string(), 1.0);
+    values.insert("x1".to_string(), 0.0);
+    values.insert("d1_1".to_string(), 1.0);
+    values.insert("d1_2".to_string(), 1.0);
+    values.insert("d2_2".to_string(), 1.0);
+
+    let solution = model.decode(&values, DEFAULT_TOLERANCE);
+
+    assert_eq!(solution.seeds, vec![0]);
+    assert_eq!(solution.activation, vec![Some(0), Some(1), Some(2)]);
+}
+
+#[test]
+fn test_bigm_multiple_product() {
+    let mut pathway = chain_pathway();
+    let mut reaction = Reaction::new(2, "R2".to_string());
+    reaction.add_substrate(2);
+    reaction.add_product(0);
+    reaction.add_product(1);
+    pathway.add_reaction(reaction);
+
+    let model = BigMModel::new(&pathway, 3);
+    let variables = model.variables();
+
+    assert_eq!(
+        variables.get("u0_2"),
+        Some(&ModelVariable::Production(0, 2))
+    );
+    assert_eq!(
+        variables.get("u1_2"),
+        Some(&ModelVariable::Production(1, 2))
+    );
+    assert_eq!(variables.get("tr2"), Some(&ModelVariable::ReactionTime(2)));
+
+    let problem = model.build();
+    assert!(!problem.constraints.is_empty());
+}
+
+#[test]
+fn test_acyclic_cuts() {
+    // A -> B -> C -> A
+    let mut pathway = chain_pathway();
+    let mut reaction = Reaction::new(2, "R2".to_string());
+    reaction.add_substrate(2);
+    reaction.add_product(0);
+    pathway.add_reaction(reaction);
+
+    let mut model = AcyclicModel::new(&pathway);
+    assert_eq!(model.incidence().compound_sccs(), vec![vec![0, 1, 2]]);
+    assert_eq!(model.get_cuts(), &vec![vec![0, 1, 2]]);
+
+    assert_eq!(model.separate(&[1]), None);
+    assert_eq!(model.separate(&[]), Some(vec![0, 1, 2]));
+    assert!(!model.add_cut(vec![2, 1, 0]));
+    assert!(model.add_cut(vec![1, 2]));
+}
+
+#[test]
+fn test_acyclic_violated_cut_already_present() {
+    // A -> B -> C -> A e D isolato: il seed set {D} viola il taglio della
+    // componente fortemente connessa, che e' gia' nel modello
+    let mut pathway = chain_pathway();
+    let mut reaction = Reaction::new(2, "R2".to_string());
+    reaction.add_substrate(2);
+    reaction.add_product(0);
+    pathway.add_reaction(reaction);
+    pathway.add_compound(Compound::new(3, "D".to_string()));
+
+    let mut model = AcyclicModel::new(&pathway);
+    assert!(model.needs_separation());
+
+    let solution = SeedSolution {
+        seeds: vec![3],
+        activation: vec![None, None, None, Some(0)],
+    };
+    let error = model.add_violated_cuts(&solution).unwrap_err();
+    assert!(error.contains("already in the model"));
+}
+
+#[test]
+fn test_time_violations() {
+    let pathway = chain_pathway();
+
+    let mut values = HashMap::new();
+    values.insert("x0".to_string(), 1.0);
+    values.insert("d1_1".to_string(), 1.0);
+    values.insert("d2_2".to_string(), 1.0);
+
+    let model = TimeSetModel::new(&pathway, 4);
+    let solution = model.decode(&values, DEFAULT_TOLERANCE);
+    assert!(time_violations(&model, &pathway, &solution).is_empty());
+
+    // C can't be available before B
+    values.insert("d2_1".to_string(), 1.0);
+    let solution = model.decode(&values, DEFAULT_TOLERANCE);
+    assert_eq!(
+        time_violations(&model, &pathway, &solution),
+        vec![TimeViolation {
+            compound: 2,
+            claimed: 1,
+            earliest: Some(2)
+        }]
+    );
+
+    // the products of the seeds may start at time 0 in the big-M models
+    let model = BigMModel::new(&pathway, 3);
+    let mut values = HashMap::new();
+    values.insert("x0".to_string(), 1.0);
+    values.insert("tm1".to_string(), 0.0);
+    values.insert("tm2".to_string(), 0.0);
+    let solution = model.decode(&values, DEFAULT_TOLERANCE);
+    let expected = vec![TimeViolation {
+        compound: 2,
+        claimed: 1,
+        earliest: Some(2),
+    }];
+    assert_eq!(time_violations(&model, &pathway, &solution), expected);
+
+    // the solvers that only write the nonzero values omit tm = 0
+    values.remove("tm1");
+    values.remove("tm2");
+    let solution = model.decode(&values, DEFAULT_TOLERANCE);
+    assert_eq!(solution.activation, vec![Some(0), Some(0), Some(0)]);
+    assert_eq!(time_violations(&model, &pathway, &solution), expected);
+}
+
+#[test]
+fn test_decode_tolerance() {
+    let pathway = chain_pathway();
+    let model = BigMModel::new(&pathway, 3);
+
+    let mut values = HashMap::new();
+    values.insert("x0".to_string(), 0.9999);
+    values.insert("x1".to_string(), 0.6);
+
+    assert!(model.decode(&values, DEFAULT_TOLERANCE).seeds.is_empty());
+    assert_eq!(model.decode(&values, 1e-3).seeds, vec![0]);
+}
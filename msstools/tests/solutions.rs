@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use msstools::models::acyclicmodel::AcyclicModel;
+use msstools::models::mssmodel::MssModel;
+use msstools::pw::{Compound, Pathway, Reaction};
+use msstools::solutions::{
+    detect_format, fractional_values, is_one, parse_solution, seed_names, seed_variable_map,
+    SlackSummary, SolutionContent, SolutionFormat, VariableMap, DEFAULT_TOLERANCE,
+};
+
+const CPLEX: &str = r#"<?xml version = "1.0" encoding="UTF-8" standalone="yes"?>
+<CPLEXSolution version="1.2">
+ <header problemName="mss"/>
+ <linearConstraints>
+  <constraint name="c1" index="0" slack="0"/>
+ </linearConstraints>
+ <variables>
+  <variable name="x0" index="0" value="1"/>
+  <variable name="x1" index="1" value="0"/>
+ </variables>
+ <objectiveValues>
+  <objective index="0" name="obj" value="1"/>
+ </objectiveValues>
+</CPLEXSolution>
+"#;
+
+const GUROBI: &str = "# Solution for model mss
+# Objective value = 1
+x0 1
+x1 0
+";
+
+#[test]
+fn test_detect_formats() {
+    assert_eq!(detect_format(CPLEX), SolutionFormat::Cplex);
+    assert_eq!(detect_format(GUROBI), SolutionFormat::Gurobi);
+    assert_eq!(
+        detect_format("Optimal - objective value 1\n  0 x0 1 1\n"),
+        SolutionFormat::Cbc
+    );
+    assert_eq!(
+        detect_format("Problem:    mss\nRows:       4\n"),
+        SolutionFormat::Glpk
+    );
+    assert_eq!(
+        detect_format("Model status\nOptimal\n"),
+        SolutionFormat::Highs
+    );
+    assert_eq!(
+        detect_format("solution status: optimal solution found\n"),
+        SolutionFormat::Scip
+    );
+    assert_eq!(detect_format("x0 1\nx1 0\n"), SolutionFormat::Gurobi);
+    assert_eq!(detect_format("x0 1\nu2_1 0\n"), SolutionFormat::Gurobi);
+    // seed names containing a space and a number
+    assert_eq!(
+        detect_format("Vitamin 12\nCoenzyme 10\n"),
+        SolutionFormat::SeedList
+    );
+    assert_eq!(detect_format("t1 2\nt2 3\n"), SolutionFormat::SeedList);
+    assert_eq!(detect_format("A\nC\n"), SolutionFormat::SeedList);
+}
+
+#[test]
+fn test_parse_values() {
+    for (text, format) in [
+        (CPLEX, SolutionFormat::Cplex),
+        (GUROBI, SolutionFormat::Gurobi),
+    ] {
+        let solution = parse_solution(text, format).unwrap();
+        assert_eq!(solution.objective, Some(1.0));
+        let SolutionContent::Values(values) = solution.content else {
+            panic!("Expected variable values");
+        };
+        assert_eq!(values.len(), 2);
+        assert_eq!(values.get("x0"), Some(&1.0));
+        assert_eq!(values.get("x1"), Some(&0.0));
+    }
+}
+
+#[test]
+fn test_cplex_slack_summary() {
+    let solution = parse_solution(CPLEX, SolutionFormat::Cplex).unwrap();
+    assert_eq!(
+        solution.slack,
+        Some(SlackSummary {
+            constraints: 1,
+            binding: 1,
+            min: 0.0,
+            max: 0.0
+        })
+    );
+}
+
+#[test]
+fn test_parse_seed_names() {
+    let content = parse_solution("# seeds\nA\n\n  C  \n", SolutionFormat::SeedList)
+        .unwrap()
+        .content;
+    assert_eq!(
+        content,
+        SolutionContent::Seeds(vec!["A".to_string(), "C".to_string()])
+    );
+}
+
+#[test]
+fn test_seeds_by_compound_name() {
+    // A -> B, with the compound ids not following the names
+    let mut pathway = Pathway::new();
+    pathway.add_compound(Compound::new(0, "B".to_string()));
+    pathway.add_compound(Compound::new(1, "A".to_string()));
+
+    let mut reaction = Reaction::new(0, "R0".to_string());
+    reaction.add_substrate(1);
+    reaction.add_product(0);
+    pathway.add_reaction(reaction);
+
+    let model = AcyclicModel::new(&pathway);
+    let map = seed_variable_map(&model.variables(), &pathway);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get("x1"), Some(&"A".to_string()));
+
+    let values = HashMap::from([
+        ("x0".to_string(), 0.0),
+        ("x1".to_string(), 1.0),
+        ("u0_0".to_string(), 1.0),
+    ]);
+    assert_eq!(
+        seed_names(&values, &map, DEFAULT_TOLERANCE),
+        vec!["A".to_string()]
+    );
+}
+
+#[test]
+fn test_integrality_tolerance() {
+    let values = HashMap::from([
+        ("x0".to_string(), 0.9999999),
+        ("x1".to_string(), 1e-10),
+        ("x2".to_string(), 0.5),
+        ("t1".to_string(), 2.00000001),
+    ]);
+
+    assert!(is_one(values["x0"], DEFAULT_TOLERANCE));
+    assert!(!is_one(values["x2"], DEFAULT_TOLERANCE));
+    assert_eq!(
+        fractional_values(&values, DEFAULT_TOLERANCE),
+        vec![("x2".to_string(), 0.5)]
+    );
+
+    let map = VariableMap::from([
+        ("x0".to_string(), "A".to_string()),
+        ("x1".to_string(), "B".to_string()),
+        ("x2".to_string(), "C".to_string()),
+    ]);
+    assert_eq!(
+        seed_names(&values, &map, DEFAULT_TOLERANCE),
+        vec!["A".to_string()]
+    );
+}
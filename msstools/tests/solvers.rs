@@ -0,0 +1,189 @@
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use msstools::formats::ModelFormat;
+use msstools::solvers::{solver_runner, SolverKind, SolverOptions, Status};
+
+#[test]
+fn test_cbc_solution() {
+    let output = "Optimal - objective value 1.00000000
+      0 x0                       1                       1
+      3 t1                       2                       0
+";
+    let runner = solver_runner(SolverKind::Cbc);
+    let solution = runner.read_solution(&mut Cursor::new(output)).unwrap();
+
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(1.0));
+    assert_eq!(solution.values.get("x0"), Some(&1.0));
+    assert_eq!(solution.values.get("t1"), Some(&2.0));
+    assert_eq!(solution.values.get("x1"), None);
+}
+
+#[test]
+fn test_glpk_solution() {
+    let output = "Problem:    
+Rows:       4
+Columns:    3 (3 integer, 3 binary)
+Non-zeros:  6
+Status:     INTEGER OPTIMAL
+Objective:  obj = 1 (MINimum)
+
+   No.   Row name        Activity     Lower bound   Upper bound
+------ ------------    ------------- ------------- -------------
+     1 c1                          1             1               
+
+   No. Column name       Activity     Lower bound   Upper bound
+------ ------------    ------------- ------------- -------------
+     1 x0           *              1             0             1 
+     2 a_very_long_variable_name
+                    *              0             0             1 
+     3 x2           *              0             0             1 
+
+Integer feasibility conditions:
+";
+    let runner = solver_runner(SolverKind::Glpk);
+    let solution = runner.read_solution(&mut Cursor::new(output)).unwrap();
+
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(1.0));
+    assert_eq!(solution.values.len(), 3);
+    assert_eq!(solution.values.get("x0"), Some(&1.0));
+    assert_eq!(solution.values.get("a_very_long_variable_name"), Some(&0.0));
+}
+
+#[test]
+fn test_highs_solution() {
+    let output = "Model status
+Optimal
+
+# Primal solution values
+Feasible
+Objective 1
+# Columns 2
+x0 1
+x1 0
+# Rows 1
+c1 1
+
+# Dual solution values
+None
+";
+    let runner = solver_runner(SolverKind::Highs);
+    let solution = runner.read_solution(&mut Cursor::new(output)).unwrap();
+
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(1.0));
+    assert_eq!(solution.values.len(), 2);
+    assert_eq!(solution.values.get("x0"), Some(&1.0));
+}
+
+#[test]
+fn test_scip_solution() {
+    let output = "solution status: optimal solution found
+objective value:                                    1
+x0                                                  1 \t(obj:1)
+t2                                                  2 \t(obj:0)
+";
+    let runner = solver_runner(SolverKind::Scip);
+    let solution = runner.read_solution(&mut Cursor::new(output)).unwrap();
+
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(1.0));
+    assert_eq!(solution.values.get("t2"), Some(&2.0));
+}
+
+#[test]
+fn test_solver_arguments() {
+    let options = SolverOptions {
+        time_limit: Some(60),
+        threads: None,
+        command: None,
+    };
+
+    let runner = solver_runner(SolverKind::Glpk);
+    let model = Path::new("model.mps");
+    let solution = runner.solution_path(model);
+    let args = runner.arguments(model, ModelFormat::Mps, &solution, &options);
+
+    assert_eq!(
+        args,
+        vec![
+            "--mps",
+            "model.mps",
+            "--tmlim",
+            "60",
+            "-o",
+            "model.mps.glpsol.sol"
+        ]
+    );
+}
+
+#[test]
+fn test_scip_threads() {
+    let options = SolverOptions {
+        time_limit: None,
+        threads: Some(4),
+        command: None,
+    };
+
+    let runner = solver_runner(SolverKind::Scip);
+    let model = Path::new("model.lp");
+    let args = runner.arguments(model, ModelFormat::Lp, Path::new("model.sol"), &options);
+
+    assert!(args.contains(&"set parallel maxnthreads 4".to_string()));
+    assert!(args.contains(&"concurrentopt".to_string()));
+    assert!(!args.contains(&"optimize".to_string()));
+}
+
+#[test]
+fn test_model_path() {
+    let model = Path::new("model.txt");
+
+    let cbc = solver_runner(SolverKind::Cbc);
+    assert_eq!(
+        cbc.model_path(model, ModelFormat::Lp),
+        Path::new("model.txt.lp")
+    );
+    assert_eq!(
+        cbc.model_path(model, ModelFormat::FreeMps),
+        Path::new("model.txt.mps")
+    );
+    assert_eq!(
+        cbc.model_path(Path::new("model.mps"), ModelFormat::Mps),
+        Path::new("model.mps")
+    );
+
+    // glpsol gets the format as an option
+    let glpk = solver_runner(SolverKind::Glpk);
+    assert_eq!(glpk.model_path(model, ModelFormat::Lp), model);
+}
+
+#[test]
+fn test_highs_options_file_removed() {
+    let options = SolverOptions {
+        time_limit: None,
+        threads: Some(2),
+        command: Some("true".to_string()),
+    };
+
+    let runner = solver_runner(SolverKind::Highs);
+    let dir = env::temp_dir().join(format!("msstools-highs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let model = dir.join("model.lp");
+    fs::write(&model, "").unwrap();
+
+    let solution = runner.solution_path(&model);
+    let (options_file, contents) = runner.auxiliary_files(&solution, &options).remove(0);
+    assert_eq!(contents, "threads = 2\n");
+    let args = runner.arguments(&model, ModelFormat::Lp, &solution, &options);
+    assert!(args.contains(&options_file.to_string_lossy().into_owned()));
+
+    // `true` doesn't write a solution, but the options file must be gone
+    assert!(runner.run(&model, ModelFormat::Lp, &options).is_err());
+    assert!(!options_file.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}